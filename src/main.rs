@@ -1,10 +1,18 @@
-use std::{env, error::Error, sync::Arc};
+use std::{collections::HashMap, env, error::Error, sync::Arc};
 use tokio::sync::RwLock;
 use twilight_cache_inmemory::ResourceType;
 use twilight_gateway::Intents;
+use twilight_model::channel::message::component::{ActionRow, Button, ButtonStyle, Component};
+use twilight_model::channel::Message;
 use twilight_model::gateway::payload::incoming::MessageCreate;
+use twilight_model::id::{
+    marker::{ChannelMarker, UserMarker},
+    Id,
+};
+use twilight_model::user::User;
 use twilight_util::builder::InteractionResponseDataBuilder as ResponseBuilder;
-use typemap_rev::{TypeMap, TypeMapKey};
+use typemap_rev::TypeMapKey;
+use wab::check::guild_only;
 use wab::SetupContext;
 
 #[tokio::main]
@@ -13,7 +21,10 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
         .with_max_level(tracing::Level::DEBUG)
         .init();
 
-    let intents = Intents::GUILD_MESSAGES | Intents::DIRECT_MESSAGES | Intents::MESSAGE_CONTENT;
+    let intents = Intents::GUILDS
+        | Intents::GUILD_MESSAGES
+        | Intents::DIRECT_MESSAGES
+        | Intents::MESSAGE_CONTENT;
     let resource_types = ResourceType::CHANNEL
         | ResourceType::EMOJI
         | ResourceType::GUILD
@@ -27,7 +38,17 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
         | ResourceType::VOICE_STATE
         | ResourceType::STICKER;
 
-    let bot = wab::Bot::builder().group(&CMD_GROUP).build();
+    let mut builder = wab::Bot::builder()
+        .group(&CMD_GROUP)
+        .prefix("!")
+        .registration_record("registered_commands.txt");
+    if let Ok(guild_id) = env::var("WAB_GUILD_ID") {
+        builder = builder.guilds(vec![Id::new(guild_id.parse()?)]);
+    }
+    if let Ok(locale_dir) = env::var("WAB_LOCALE_DIR") {
+        builder = builder.localizations(wab::Localizations::load_dir("en-US", locale_dir)?);
+    }
+    let bot = builder.build();
 
     bot.run(
         env::var("WAB_TOKEN")?,
@@ -53,21 +74,57 @@ fn setup(ctx: &mut SetupContext) {
 }
 
 #[wab::event]
-async fn message_create(event: &Box<MessageCreate>) {
+async fn message_create(event: &MessageCreate) {
     tracing::info!("{}", event.content);
 }
 
 #[wab::group(
     category = "category here",
-    commands(cmd, cmd2),
+    commands(cmd, cmd2, color, sum, greet, prefix, feedback, user_info, quote_message),
     events(message_create),
-    setup = setup
+    components(counter),
+    modals(feedback_submit),
+    setup = setup,
+    on_error = on_cmd_error,
+    checks(not_bot),
+    command_groups(NameGroup, Name2Group, Cmd2Group)
 )]
 pub struct CmdGroup;
 
+#[wab::command_group(
+    name = "name",
+    description = "nested example commands",
+    description_localizations("de" = "verschachtelte Beispielbefehle")
+)]
+pub struct NameGroup;
+
+#[wab::command_group(name = "name name2", description = "more nested example commands")]
+pub struct Name2Group;
+
+#[wab::command_group(name = "cmd2", description = "second example command group")]
+pub struct Cmd2Group;
+
+#[wab::box_async]
+async fn on_cmd_error(name: String, ctx: wab::CommandContext, error: wab::Error) {
+    tracing::warn!("{name} failed: {error}");
+    let _ = ctx
+        .respond(
+            ResponseBuilder::new()
+                .content(format!("`{name}` failed: {error}"))
+                .build(),
+        )
+        .await;
+}
+
+#[wab::box_async]
+async fn not_bot(ctx: wab::CommandContext) -> bool {
+    ctx.author().is_some_and(|user| !user.bot)
+}
+
 #[wab::command(
     name = "name name2 name3",
     description = "description here",
+    cooldown(per = "user", rate = 2, seconds = 10),
     parameter(
         name = "arg1",
         description = "boing",
@@ -95,27 +152,253 @@ pub async fn cmd(
     mut arg2: String,
     mut arg3: Option<f64>,
 ) -> wab::CommandResult {
-    println!("boing1");
+    tracing::debug!("running cmd");
+    ctx.defer(false).await?;
     let lock = ctx.state.get::<CmdState>().await;
 
     let count = {
         let mut counter = lock.write().await;
-        counter.x = counter.x + 1;
+        counter.x += 1;
         counter.x
     };
-    println!("count: {}", count);
+    tracing::info!("count: {}", count);
 
     ctx.respond(
         ResponseBuilder::new()
-            .content(format!("count: {count}\n{arg1:?}, {arg2:?}, {arg3:?}"))
+            .content(format!(
+                "{}\n{arg1:?}, {arg2:?}, {arg3:?}",
+                ctx.t("cmd.count", &[("count", &count)])
+            ))
             .build(),
     )
-    .await;
+    .await?;
+
+    Ok(())
+}
+
+/// cmd2 desc
+#[wab::command(
+    name = "cmd2 name",
+    permissions = "MANAGE_MESSAGES | MODERATE_MEMBERS",
+    dm = false
+)]
+pub async fn cmd2(
+    ctx: wab::CommandContext,
+    /// a user
+    user: User,
+    /// a text channel
+    #[channel_types(GuildText, GuildAnnouncement)]
+    channel: Option<Id<ChannelMarker>>,
+    #[description = "a fruit"]
+    #[autocomplete = complete_fruit]
+    fruit: Option<String>,
+) -> wab::CommandResult {
+    ctx.respond(
+        ResponseBuilder::new()
+            .content(format!("{} {channel:?} {fruit:?}", user.name))
+            .components([counter_button(0)?])
+            .build(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn counter_button(count: i64) -> Result<Component, wab::CustomIdError> {
+    Ok(Component::ActionRow(ActionRow {
+        components: vec![Component::Button(Button {
+            custom_id: Some(counter_custom_id(count)?),
+            disabled: false,
+            emoji: None,
+            label: Some(format!("clicked {count} times")),
+            style: ButtonStyle::Primary,
+            url: None,
+        })],
+    }))
+}
+
+#[wab::component(prefix = "counter")]
+pub async fn counter(ctx: wab::ComponentContext, count: i64) -> wab::CommandResult {
+    ctx.update_message(
+        ResponseBuilder::new()
+            .components([counter_button(count + 1)?])
+            .build(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[wab::box_async]
+async fn complete_fruit(
+    _ctx: wab::AutocompleteContext,
+    value: String,
+    _args: HashMap<String, wab::Argument>,
+) -> Vec<wab::ParameterChoice> {
+    ["apple", "banana", "cherry"]
+        .into_iter()
+        .filter(|x| x.starts_with(&value))
+        .map(|x| wab::ParameterChoice::new(x, wab::ParameterChoiceType::String(x.to_string())))
+        .collect()
+}
+
+struct Color(u32);
+
+impl wab::FromArgument for Color {
+    const KIND: wab::ParameterType = wab::ParameterType::String;
+    fn constrain(parameter: &mut wab::ParameterBuilder) -> &mut wab::ParameterBuilder {
+        parameter.min_length(Some(6)).max_length(Some(7))
+    }
+    fn from_argument(argument: wab::Argument) -> Result<Self, String> {
+        let wab::Argument::String(x) = argument else {
+            return Err(String::from("argument type mismatched"));
+        };
+        u32::from_str_radix(x.trim_start_matches('#'), 16)
+            .map(Color)
+            .map_err(|_| format!("'{x}' is not a hex color"))
+    }
+}
+
+#[derive(wab::Choices)]
+enum ColorFormat {
+    Hex,
+    #[choice(name = "RGB")]
+    Rgb,
+}
+
+/// show a color
+#[wab::command(name = "color")]
+pub async fn color(
+    ctx: wab::CommandContext,
+    /// a hex color such as #ff8800
+    color: Color,
+    /// how to show the color
+    #[default(ColorFormat::Hex)]
+    format: ColorFormat,
+) -> wab::CommandResult {
+    let content = match format {
+        ColorFormat::Hex => format!("#{:06x}", color.0),
+        ColorFormat::Rgb => format!(
+            "rgb({}, {}, {})",
+            color.0 >> 16 & 0xff,
+            color.0 >> 8 & 0xff,
+            color.0 & 0xff
+        ),
+    };
+    ctx.respond(ResponseBuilder::new().content(content).build())
+        .await?;
+
+    Ok(())
+}
+
+/// add up numbers
+#[wab::command(name = "sum")]
+pub async fn sum(
+    ctx: wab::CommandContext,
+    /// numbers separated by spaces
+    #[rest]
+    numbers: Vec<i64>,
+) -> wab::CommandResult {
+    let total: i64 = numbers.iter().sum();
+    ctx.respond(ResponseBuilder::new().content(total.to_string()).build())
+        .await?;
 
     Ok(())
 }
 
-#[wab::command(name = "cmd2 name", description = "cmd2 desc")]
-pub async fn cmd2(ctx: wab::CommandContext) -> wab::CommandResult {
+/// greet up to 3 users
+#[wab::command(name = "greet")]
+pub async fn greet(
+    ctx: wab::CommandContext,
+    /// a user to greet
+    #[max_count = 3]
+    user: Vec<Id<UserMarker>>,
+) -> wab::CommandResult {
+    let mentions: Vec<String> = user.iter().map(|id| format!("<@{id}>")).collect();
+    ctx.respond(
+        ResponseBuilder::new()
+            .content(format!("Hello {}!", mentions.join(" ")))
+            .build(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// change the text command prefix for this server
+#[wab::command(name = "prefix", permissions = "MANAGE_GUILD", dm = false)]
+pub async fn prefix(
+    ctx: wab::CommandContext,
+    /// the new prefix
+    #[max_length = 5]
+    prefix: String,
+) -> wab::CommandResult {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Err(wab::Error::user("This command only works in servers."));
+    };
+    ctx.state
+        .get::<wab::Prefixes>()
+        .await
+        .set(guild_id, prefix.as_str());
+    ctx.respond(
+        ResponseBuilder::new()
+            .content(format!("Text commands now use `{prefix}`."))
+            .build(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[derive(wab::Modal)]
+#[modal(title = "Feedback")]
+pub struct FeedbackForm {
+    #[input(label = "Subject", max_length = 50)]
+    subject: String,
+    #[input(label = "Details", style = "paragraph")]
+    details: Option<String>,
+}
+
+#[wab::command(name = "feedback", description = "send feedback", checks(guild_only))]
+pub async fn feedback(ctx: wab::CommandContext) -> wab::CommandResult {
+    ctx.respond_modal::<FeedbackForm>(feedback_submit_custom_id()?)
+        .await?;
+
+    Ok(())
+}
+
+#[wab::modal(prefix = "feedback")]
+pub async fn feedback_submit(ctx: wab::ModalContext, form: FeedbackForm) -> wab::CommandResult {
+    ctx.respond(
+        ResponseBuilder::new()
+            .content(format!("{}: {:?}", form.subject, form.details))
+            .build(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[wab::user_command(name = "User Info")]
+pub async fn user_info(ctx: wab::CommandContext, user: User) -> wab::CommandResult {
+    ctx.respond(
+        ResponseBuilder::new()
+            .content(format!("{} ({})", user.name, user.id))
+            .build(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[wab::message_command(name = "Quote")]
+pub async fn quote_message(ctx: wab::CommandContext, message: Message) -> wab::CommandResult {
+    ctx.respond(
+        ResponseBuilder::new()
+            .content(format!("> {}\n- {}", message.content, message.author.name))
+            .build(),
+    )
+    .await?;
+
     Ok(())
 }
@@ -1,21 +1,344 @@
-use twilight_model::application::interaction::application_command::CommandOptionValue;
+use crate::parameter::{ParameterBuilder, ParameterType};
+use crate::Error;
+use twilight_model::application::command::CommandType;
+use twilight_model::application::interaction::application_command::{
+    CommandInteractionDataResolved, CommandOptionValue, InteractionChannel, InteractionMember,
+};
+use twilight_model::channel::{Attachment, Message};
+use twilight_model::guild::Role;
+use twilight_model::id::{
+    marker::{
+        AttachmentMarker, ChannelMarker, GenericMarker, MessageMarker, RoleMarker, UserMarker,
+    },
+    Id,
+};
+use twilight_model::user::User;
 
 pub enum Argument {
     String(String),
     Integer(i64),
     Boolean(bool),
     Float(f64),
+    User {
+        id: Id<UserMarker>,
+        user: Option<User>,
+        member: Option<InteractionMember>,
+    },
+    Channel {
+        id: Id<ChannelMarker>,
+        channel: Option<InteractionChannel>,
+    },
+    Role {
+        id: Id<RoleMarker>,
+        role: Option<Role>,
+    },
+    Mentionable {
+        id: Id<GenericMarker>,
+        user: Option<User>,
+        member: Option<InteractionMember>,
+        role: Option<Role>,
+    },
+    Attachment {
+        id: Id<AttachmentMarker>,
+        attachment: Option<Attachment>,
+    },
+    Message {
+        id: Id<MessageMarker>,
+        message: Option<Box<Message>>,
+    },
+}
+impl Argument {
+    pub fn resolve_target(
+        kind: CommandType,
+        target_id: Id<GenericMarker>,
+        resolved: Option<&CommandInteractionDataResolved>,
+    ) -> Option<Self> {
+        let argument = match kind {
+            CommandType::User => Self::User {
+                id: target_id.cast(),
+                user: resolved
+                    .and_then(|r| r.users.get(&target_id.cast()))
+                    .cloned(),
+                member: resolved
+                    .and_then(|r| r.members.get(&target_id.cast()))
+                    .cloned(),
+            },
+            CommandType::Message => Self::Message {
+                id: target_id.cast(),
+                message: resolved
+                    .and_then(|r| r.messages.get(&target_id.cast()))
+                    .map(|m| Box::new(m.clone())),
+            },
+            _ => return None,
+        };
+        Some(argument)
+    }
+    pub fn resolve(
+        v: &CommandOptionValue,
+        resolved: Option<&CommandInteractionDataResolved>,
+    ) -> Option<Self> {
+        let argument = match v {
+            CommandOptionValue::Boolean(x) => Self::Boolean(*x),
+            CommandOptionValue::Integer(x) => Self::Integer(*x),
+            CommandOptionValue::Number(x) => Self::Float(*x),
+            CommandOptionValue::String(x) => Self::String(x.to_string()),
+            CommandOptionValue::User(id) => Self::User {
+                id: *id,
+                user: resolved.and_then(|r| r.users.get(id)).cloned(),
+                member: resolved.and_then(|r| r.members.get(id)).cloned(),
+            },
+            CommandOptionValue::Channel(id) => Self::Channel {
+                id: *id,
+                channel: resolved.and_then(|r| r.channels.get(id)).cloned(),
+            },
+            CommandOptionValue::Role(id) => Self::Role {
+                id: *id,
+                role: resolved.and_then(|r| r.roles.get(id)).cloned(),
+            },
+            CommandOptionValue::Mentionable(id) => Self::Mentionable {
+                id: *id,
+                user: resolved.and_then(|r| r.users.get(&id.cast())).cloned(),
+                member: resolved.and_then(|r| r.members.get(&id.cast())).cloned(),
+                role: resolved.and_then(|r| r.roles.get(&id.cast())).cloned(),
+            },
+            CommandOptionValue::Attachment(id) => Self::Attachment {
+                id: *id,
+                attachment: resolved.and_then(|r| r.attachments.get(id)).cloned(),
+            },
+            _ => return None,
+        };
+        Some(argument)
+    }
 }
 
 impl TryFrom<&CommandOptionValue> for Argument {
-    type Error = ();
+    type Error = Error;
     fn try_from(v: &CommandOptionValue) -> Result<Self, Self::Error> {
-        match v {
-            CommandOptionValue::Boolean(x) => Ok(Self::Boolean(*x)),
-            CommandOptionValue::Integer(x) => Ok(Self::Integer(*x)),
-            CommandOptionValue::Number(x) => Ok(Self::Float(*x)),
-            CommandOptionValue::String(x) => Ok(Self::String(x.to_string())),
-            _ => Err(()),
+        Self::resolve(v, None)
+            .ok_or_else(|| Error::Other(format!("unsupported option value: {v:?}").into()))
+    }
+}
+
+pub trait FromArgument: Sized {
+    const KIND: ParameterType;
+    fn required() -> bool {
+        true
+    }
+    fn constrain(parameter: &mut ParameterBuilder) -> &mut ParameterBuilder {
+        parameter
+    }
+    fn from_argument(argument: Argument) -> Result<Self, String>;
+    fn from_missing() -> Result<Self, String> {
+        Err(String::from("missing required argument"))
+    }
+    fn from_text(text: &str) -> Result<Self, String> {
+        Self::from_argument(Argument::String(String::from(text)))
+    }
+    fn describe(&self) -> Option<String> {
+        None
+    }
+}
+
+pub trait FromTarget: Sized {
+    const KIND: CommandType;
+    fn from_target(argument: Argument) -> Result<Self, String>;
+    fn from_missing() -> Result<Self, String> {
+        Err(String::from("missing target"))
+    }
+}
+
+impl<T: FromTarget> FromTarget for Option<T> {
+    const KIND: CommandType = T::KIND;
+    fn from_target(argument: Argument) -> Result<Self, String> {
+        T::from_target(argument).map(Some)
+    }
+    fn from_missing() -> Result<Self, String> {
+        Ok(None)
+    }
+}
+
+macro_rules! impl_from_target {
+    ($ty:ty, $kind:ident, $pattern:pat => $value:expr) => {
+        impl FromTarget for $ty {
+            const KIND: CommandType = CommandType::$kind;
+            fn from_target(argument: Argument) -> Result<Self, String> {
+                match argument {
+                    $pattern => Ok($value),
+                    _ => Err(String::from("target type mismatched")),
+                }
+            }
+        }
+    };
+}
+
+impl_from_target!(Id<UserMarker>, User, Argument::User { id, .. } => id);
+impl_from_target!(User, User, Argument::User { user: Some(x), .. } => x);
+impl_from_target!(InteractionMember, User, Argument::User { member: Some(x), .. } => x);
+impl_from_target!(Id<MessageMarker>, Message, Argument::Message { id, .. } => id);
+impl_from_target!(Message, Message, Argument::Message { message: Some(x), .. } => *x);
+
+pub fn from_delimited<T: FromArgument>(
+    text: &str,
+    delimiter: Option<&str>,
+) -> Result<Vec<T>, String> {
+    match delimiter {
+        Some(delimiter) => text
+            .split(delimiter)
+            .map(str::trim)
+            .filter(|x| !x.is_empty())
+            .map(T::from_text)
+            .collect(),
+        None => text.split_whitespace().map(T::from_text).collect(),
+    }
+}
+
+pub fn check_count(count: usize, min: usize, max: Option<usize>) -> Result<(), String> {
+    if count < min {
+        return Err(format!("expected at least {min} {}", values(min)));
+    }
+    match max {
+        Some(max) if count > max => Err(format!("expected at most {max} {}", values(max))),
+        _ => Ok(()),
+    }
+}
+
+fn values(count: usize) -> &'static str {
+    if count == 1 {
+        "value"
+    } else {
+        "values"
+    }
+}
+
+pub(crate) fn parse_mention<T>(text: &str, prefixes: &[&str]) -> Result<Id<T>, String> {
+    let id = prefixes
+        .iter()
+        .find_map(|prefix| text.strip_prefix(prefix)?.strip_suffix('>'))
+        .unwrap_or(text);
+    id.parse()
+        .map_err(|_| format!("'{text}' is not a valid mention or id"))
+}
+
+impl<T: FromArgument> FromArgument for Option<T> {
+    const KIND: ParameterType = T::KIND;
+    fn required() -> bool {
+        false
+    }
+    fn constrain(parameter: &mut ParameterBuilder) -> &mut ParameterBuilder {
+        T::constrain(parameter)
+    }
+    fn from_argument(argument: Argument) -> Result<Self, String> {
+        T::from_argument(argument).map(Some)
+    }
+    fn from_missing() -> Result<Self, String> {
+        Ok(None)
+    }
+    fn from_text(text: &str) -> Result<Self, String> {
+        T::from_text(text).map(Some)
+    }
+}
+
+macro_rules! impl_from_argument {
+    ($ty:ty, $kind:ident, $pattern:pat => $value:expr) => {
+        impl_from_argument!($ty, $kind, $pattern => $value, |text| {
+            Self::from_argument(Argument::String(String::from(text)))
+        });
+    };
+    ($ty:ty, $kind:ident, $pattern:pat => $value:expr, |$text:ident| $from_text:expr) => {
+        impl FromArgument for $ty {
+            const KIND: ParameterType = ParameterType::$kind;
+            fn from_argument(argument: Argument) -> Result<Self, String> {
+                match argument {
+                    $pattern => Ok($value),
+                    _ => Err(String::from("argument type mismatched")),
+                }
+            }
+            fn from_text($text: &str) -> Result<Self, String> {
+                $from_text
+            }
         }
+    };
+}
+
+impl_from_argument!(String, String, Argument::String(x) => x);
+impl_from_argument!(i64, Integer, Argument::Integer(x) => x, |text| {
+    text.parse().map_err(|_| format!("'{text}' is not a valid integer"))
+});
+impl_from_argument!(f64, Float, Argument::Float(x) => x, |text| {
+    text.parse().map_err(|_| format!("'{text}' is not a valid number"))
+});
+impl_from_argument!(bool, Boolean, Argument::Boolean(x) => x, |text| {
+    text.parse().map_err(|_| format!("'{text}' is not true or false"))
+});
+impl_from_argument!(Id<UserMarker>, User, Argument::User { id, .. } => id, |text| {
+    parse_mention(text, &["<@!", "<@"])
+});
+impl_from_argument!(User, User, Argument::User { user: Some(x), .. } => x);
+impl_from_argument!(InteractionMember, User, Argument::User { member: Some(x), .. } => x);
+impl_from_argument!(Id<ChannelMarker>, Channel, Argument::Channel { id, .. } => id, |text| {
+    parse_mention(text, &["<#"])
+});
+impl_from_argument!(
+    InteractionChannel,
+    Channel,
+    Argument::Channel { channel: Some(x), .. } => x
+);
+impl_from_argument!(Id<RoleMarker>, Role, Argument::Role { id, .. } => id, |text| {
+    parse_mention(text, &["<@&"])
+});
+impl_from_argument!(Role, Role, Argument::Role { role: Some(x), .. } => x);
+impl_from_argument!(
+    Id<GenericMarker>,
+    Mentionable,
+    Argument::Mentionable { id, .. } => id,
+    |text| parse_mention(text, &["<@&", "<@!", "<@"])
+);
+impl_from_argument!(Id<AttachmentMarker>, Attachment, Argument::Attachment { id, .. } => id);
+impl_from_argument!(
+    Attachment,
+    Attachment,
+    Argument::Attachment { attachment: Some(x), .. } => x
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_delimited_values() {
+        assert_eq!(from_delimited::<i64>(" 1  2\t3 ", None), Ok(vec![1, 2, 3]));
+        assert_eq!(
+            from_delimited::<i64>("1, 2,,3 ,", Some(",")),
+            Ok(vec![1, 2, 3])
+        );
+        assert_eq!(
+            from_delimited::<String>("a b; c ;", Some(";")),
+            Ok(vec![String::from("a b"), String::from("c")])
+        );
+        assert_eq!(from_delimited::<i64>("", None), Ok(Vec::new()));
+        assert!(from_delimited::<i64>("1 two", None).is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn checks_counts() {
+        assert!(check_count(2, 1, Some(3)).is_ok());
+        assert!(check_count(1, 1, Some(1)).is_ok());
+        assert!(check_count(100, 0, None).is_ok());
+        assert_eq!(
+            check_count(0, 1, None),
+            Err(String::from("expected at least 1 value"))
+        );
+        assert_eq!(
+            check_count(1, 2, None),
+            Err(String::from("expected at least 2 values"))
+        );
+        assert_eq!(
+            check_count(2, 0, Some(1)),
+            Err(String::from("expected at most 1 value"))
+        );
+        assert_eq!(
+            check_count(4, 1, Some(3)),
+            Err(String::from("expected at most 3 values"))
+        );
+    }
+}
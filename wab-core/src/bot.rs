@@ -1,71 +1,222 @@
+use crate::command::TARGET_ARGUMENT;
+use crate::error::{self, catch_panic};
+use crate::{prefix, sync};
 use crate::{
-    Argument, Client, CommandContext, CommandHandler, EventFunction, Group, SetupContext, State,
+    Argument, AutocompleteContext, Client, Command, CommandContext, CommandHandler, CommandScope,
+    ComponentContext, ComponentErrorHandler, ComponentFunction, CooldownTracker, CustomId,
+    ErrorHandler, EventFunction, Group, Localizations, ModalContext, ModalErrorHandler,
+    ModalFunction, Prefixes, RegistrationTarget, SetupContext, State, ValidationError,
 };
-use std::collections::HashMap;
+use std::collections::{hash_map::Entry, HashMap};
+use std::convert::identity;
 use std::error::Error;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use twilight_cache_inmemory::{InMemoryCache, ResourceType};
 use twilight_gateway::{Event as EventData, EventType, Intents, Shard, ShardId};
-use twilight_http::client::InteractionClient;
+use twilight_model::application::command::{Command as ApplicationCommand, CommandType};
 use twilight_model::application::interaction::{
-    application_command::{CommandData, CommandOptionValue},
+    application_command::{
+        CommandData, CommandDataOption, CommandInteractionDataResolved, CommandOptionValue,
+    },
     Interaction, InteractionData, InteractionType,
 };
-use twilight_model::id::{marker::ApplicationMarker, Id};
-use typemap_rev::TypeMap;
+use twilight_model::channel::message::MessageFlags;
+use twilight_model::channel::Message;
+use twilight_model::http::interaction::{
+    InteractionResponse, InteractionResponseData, InteractionResponseType,
+};
+use twilight_model::id::{
+    marker::{ApplicationMarker, GuildMarker},
+    Id,
+};
+use twilight_util::builder::InteractionResponseDataBuilder;
 
 pub struct EventDispatchContext {
     state: Arc<State>,
     client: Arc<Client>,
     commands: Arc<CommandHandler>,
+    cooldowns: Arc<CooldownTracker>,
     events: Arc<HashMap<EventType, Vec<EventFunction>>>,
+    components: Arc<HashMap<String, ComponentFunction>>,
+    modals: Arc<HashMap<String, ModalFunction>>,
+    on_error: ErrorHandler,
+    on_component_error: ComponentErrorHandler,
+    on_modal_error: ModalErrorHandler,
+    localizations: Option<Arc<Localizations>>,
+    scope: Arc<CommandScope>,
+    prefixes: Option<Arc<Prefixes>>,
 }
 
 pub struct Bot {
     state: Arc<State>,
     commands: Arc<CommandHandler>,
+    cooldowns: Arc<CooldownTracker>,
     events: Arc<HashMap<EventType, Vec<EventFunction>>>,
+    components: Arc<HashMap<String, ComponentFunction>>,
+    modals: Arc<HashMap<String, ModalFunction>>,
+    on_error: ErrorHandler,
+    on_component_error: ComponentErrorHandler,
+    on_modal_error: ModalErrorHandler,
+    scope: Arc<CommandScope>,
+    cleanup: Vec<RegistrationTarget>,
+    registration_record: Option<PathBuf>,
+    dry_run: bool,
+    localizations: Option<Arc<Localizations>>,
+    prefixes: Option<Arc<Prefixes>>,
+    errors: Vec<ValidationError>,
 }
 impl Bot {
-    fn new<'a>(groups: &[&'a Group]) -> Self {
+    fn new(builder: BotBuilder) -> Self {
         let mut setup_ctx: SetupContext = SetupContext::new();
         let mut commands = Vec::new();
+        let mut command_groups = Vec::new();
         let mut events: HashMap<EventType, Vec<EventFunction>> = HashMap::new();
+        let mut components = HashMap::new();
+        let mut modals = HashMap::new();
+        let mut errors = Vec::new();
 
-        for group in groups.iter() {
+        for group in builder.groups.iter() {
             for command in (group.build_commands)() {
                 commands.push(command);
             }
+            command_groups.extend((group.build_command_groups)());
             for event in (group.build_events)() {
-                events
-                    .entry(event.kind)
-                    .or_insert(Vec::new())
-                    .push(event.function);
+                events.entry(event.kind).or_default().push(event.function);
+            }
+            for component in (group.build_components)() {
+                match components.entry(component.prefix) {
+                    Entry::Occupied(o) => {
+                        errors.push(ValidationError::new(o.key(), "duplicate component prefix"))
+                    }
+                    Entry::Vacant(v) => {
+                        v.insert(component.function);
+                    }
+                };
+            }
+            for modal in (group.build_modals)() {
+                match modals.entry(modal.prefix) {
+                    Entry::Occupied(o) => {
+                        errors.push(ValidationError::new(o.key(), "duplicate modal prefix"))
+                    }
+                    Entry::Vacant(v) => {
+                        v.insert(modal.function);
+                    }
+                };
             }
             if let Some(setup) = group.setup {
                 setup(&mut setup_ctx);
             }
         }
 
+        let prefixes = (builder.prefix.is_some() || !builder.guild_prefixes.is_empty())
+            .then(|| Arc::new(Prefixes::new(builder.prefix, builder.guild_prefixes)));
+        if let Some(prefixes) = &prefixes {
+            setup_ctx.state.insert::<Prefixes>(prefixes.clone());
+        }
+
         Bot {
             state: Arc::new(State {
-                storage: RwLock::new(setup_ctx.state)
+                storage: RwLock::new(setup_ctx.state),
             }),
-            commands: Arc::new(CommandHandler::new(commands)),
+            commands: Arc::new(CommandHandler::new(commands, command_groups)),
+            cooldowns: Arc::new(CooldownTracker::new()),
             events: Arc::new(events),
+            components: Arc::new(components),
+            modals: Arc::new(modals),
+            on_error: builder.on_error,
+            on_component_error: builder.on_component_error,
+            on_modal_error: builder.on_modal_error,
+            scope: Arc::new(builder.scope),
+            cleanup: builder.cleanup,
+            registration_record: builder.registration_record,
+            dry_run: builder.dry_run,
+            localizations: builder.localizations.map(Arc::new),
+            prefixes,
+            errors,
         }
     }
-    async fn register_interactions(&self, interaction_client: &InteractionClient<'_>) {
-        let application_commands = self.commands.create_application_commands();
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = self.errors.clone();
+        errors.extend(
+            self.commands
+                .validate(&self.scope, self.localizations.as_deref()),
+        );
+        errors.sort();
+        errors
+    }
+    async fn register_interactions(
+        &self,
+        http: &twilight_http::Client,
+        application_id: Id<ApplicationMarker>,
+    ) {
+        let errors = self.validate();
+        if !errors.is_empty() {
+            for error in errors.iter() {
+                tracing::error!("invalid application command {error}");
+            }
+            tracing::error!("skipping application command registration");
+            return;
+        }
+
+        let mut payloads = self.commands.create_application_commands(&self.scope);
+        let recorded = match &self.registration_record {
+            Some(path) => sync::read_record(path).await,
+            None => Vec::new(),
+        };
+        let configured = self
+            .scope
+            .targets()
+            .into_iter()
+            .chain(self.cleanup.iter().copied());
+        for target in configured.chain(recorded) {
+            payloads.entry(target).or_default();
+        }
+
+        if let Some(localizations) = &self.localizations {
+            for command in payloads.values_mut().flatten() {
+                self.commands.localize(command, localizations);
+            }
+            let commands: Vec<ApplicationCommand> = payloads.values().flatten().cloned().collect();
+            for missing in localizations.validate(&commands) {
+                tracing::warn!("{missing}");
+            }
+        }
 
-        println!("{:#?}", application_commands);
+        let mut owned = Vec::new();
+        for (target, application_commands) in payloads {
+            tracing::debug!(?target, "{:#?}", application_commands);
 
-        let result = interaction_client
-            .set_guild_commands(Id::new(495327409487478785), &application_commands)
+            let result = sync::sync_commands(
+                http,
+                application_id,
+                target,
+                &application_commands,
+                self.dry_run,
+            )
             .await;
 
-        println!("result: {:#?}", result);
+            match result {
+                Ok(diff) if self.dry_run => {
+                    tracing::info!(?target, "application command changes (dry run):\n{diff}")
+                }
+                Ok(diff) => {
+                    tracing::info!(?target, "synced application commands:\n{diff}");
+                    if !application_commands.is_empty() {
+                        owned.push(target);
+                    }
+                }
+                Err(source) => {
+                    tracing::error!(?target, ?source, "failed to sync application commands");
+                    owned.push(target);
+                }
+            }
+        }
+
+        if let Some(path) = self.registration_record.as_ref().filter(|_| !self.dry_run) {
+            sync::write_record(path, &owned).await;
+        }
     }
     pub async fn run(
         &self,
@@ -79,9 +230,8 @@ impl Bot {
         let mut shard = Shard::new(ShardId::ONE, token.clone(), intents);
 
         let http = twilight_http::Client::new(token);
-        let interaction_client = http.interaction(application_id);
 
-        self.register_interactions(&interaction_client).await;
+        self.register_interactions(&http, application_id).await;
 
         let cache = InMemoryCache::builder()
             .resource_types(resource_types)
@@ -112,10 +262,23 @@ impl Bot {
                 state: self.state.clone(),
                 client: client.clone(),
                 commands: self.commands.clone(),
+                cooldowns: self.cooldowns.clone(),
                 events: self.events.clone(),
+                components: self.components.clone(),
+                modals: self.modals.clone(),
+                on_error: self.on_error,
+                on_component_error: self.on_component_error,
+                on_modal_error: self.on_modal_error,
+                localizations: self.localizations.clone(),
+                scope: self.scope.clone(),
+                prefixes: self.prefixes.clone(),
             };
 
-            tokio::spawn(handle_event(ctx, event));
+            tokio::spawn(async move {
+                if let Err(source) = handle_event(ctx, event).await {
+                    tracing::error!(?source, "error handling event");
+                }
+            });
         }
     }
     pub fn builder<'a>() -> BotBuilder<'a> {
@@ -132,12 +295,24 @@ async fn handle_event(
             handle_interaction(&ctx, ic.0).await?;
         }
         _ => {
+            let message = match &event {
+                EventData::MessageCreate(mc) if ctx.prefixes.is_some() => Some(mc.0.clone()),
+                _ => None,
+            };
             if let Some(event_fns) = ctx.events.get(&event.kind()) {
                 let event = Arc::new(event);
-                for event_fn in event_fns {
-                    tokio::spawn(event_fn(event.clone()));
+                for &event_fn in event_fns {
+                    let event = event.clone();
+                    tokio::spawn(async move {
+                        if let Err(error) = catch_panic(event_fn(event.clone())).await {
+                            tracing::error!(?error, "event handler for {:?} failed", event.kind());
+                        }
+                    });
                 }
             }
+            if let Some(message) = message {
+                handle_prefix_command(&ctx, message).await?;
+            }
         }
     }
     Ok(())
@@ -151,6 +326,15 @@ async fn handle_interaction(
         InteractionType::ApplicationCommand => {
             handle_application_command(ctx, interaction).await?;
         }
+        InteractionType::ApplicationCommandAutocomplete => {
+            handle_autocomplete(ctx, interaction).await?;
+        }
+        InteractionType::MessageComponent => {
+            handle_component(ctx, interaction).await?;
+        }
+        InteractionType::ModalSubmit => {
+            handle_modal(ctx, interaction).await?;
+        }
         _ => {}
     }
     Ok(())
@@ -160,51 +344,321 @@ async fn handle_application_command(
     ctx: &EventDispatchContext,
     interaction: Interaction,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let data = if let Some(InteractionData::ApplicationCommand(data)) = &interaction.data {
-        data
+    let Some(InteractionData::ApplicationCommand(data)) = &interaction.data else {
+        return Err(format!("interaction {} has no command data", interaction.id).into());
+    };
+
+    tracing::debug!("{:#?}", data);
+
+    let (cmd, name, args) = if data.kind == CommandType::ChatInput {
+        let (name, options) = command_path(data);
+        let args = collect_arguments(&name, options, data.resolved.as_ref());
+        (ctx.commands.get(&name), name, args)
     } else {
-        panic!();
+        let mut args = HashMap::new();
+        let target = data
+            .target_id
+            .and_then(|id| Argument::resolve_target(data.kind, id, data.resolved.as_ref()));
+        if let Some(target) = target {
+            args.insert(String::from(TARGET_ARGUMENT), target);
+        }
+        (
+            ctx.commands.get_context(data.kind, &data.name),
+            data.name.clone(),
+            args,
+        )
     };
 
+    let Some(cmd) = cmd else {
+        tracing::warn!("Could not find command: '{}'", name);
+        return Ok(());
+    };
+
+    let cmd_ctx = CommandContext::new(
+        ctx.state.clone(),
+        ctx.client.clone(),
+        interaction,
+        ctx.localizations.clone(),
+    );
+    run_command(ctx, cmd, name, cmd_ctx, Ok(args)).await
+}
+
+async fn handle_prefix_command(
+    ctx: &EventDispatchContext,
+    message: Message,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let Some(prefixes) = &ctx.prefixes else {
+        return Ok(());
+    };
+    if message.author.bot {
+        return Ok(());
+    }
+    let Some(prefix) = prefixes.get(message.guild_id) else {
+        return Ok(());
+    };
+    let Some(text) = message.content.strip_prefix(prefix.as_str()) else {
+        return Ok(());
+    };
+    let Some((cmd, rest)) = ctx.commands.find_prefixed(text) else {
+        tracing::debug!("Could not find prefix command: '{}'", text);
+        return Ok(());
+    };
+    if !cmd.scope().unwrap_or(&ctx.scope).includes(message.guild_id) {
+        return Ok(());
+    }
+
+    let name = String::from(cmd.name());
+    let args = if prefix::permitted(
+        &ctx.client.cache,
+        &ctx.commands.permissions(&name),
+        &message,
+    ) {
+        prefix::parse_arguments(cmd, rest, &message, &ctx.client.cache).map_err(|e| *e)
+    } else {
+        Err(crate::Error::check_failed("permissions"))
+    };
+    let cmd_ctx = CommandContext::from_message(
+        ctx.state.clone(),
+        ctx.client.clone(),
+        message,
+        ctx.localizations.clone(),
+    );
+    run_command(ctx, cmd, name, cmd_ctx, args).await
+}
+
+async fn run_command(
+    ctx: &EventDispatchContext,
+    cmd: &Command,
+    name: String,
+    cmd_ctx: CommandContext,
+    args: Result<HashMap<String, Argument>, crate::Error>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let result = catch_panic(async {
+        cmd.check(&cmd_ctx).await?;
+        let args = args?;
+        if let Some(cooldown) = cmd.cooldown() {
+            let key = cooldown.bucket.key(
+                cmd_ctx.author_id(),
+                cmd_ctx.channel_id(),
+                cmd_ctx.guild_id(),
+            );
+            if let Some(key) = key {
+                ctx.cooldowns
+                    .hit(&name, cooldown, key)
+                    .map_err(crate::Error::Cooldown)?;
+            }
+        }
+        cmd.run(cmd_ctx.clone(), args).await
+    })
+    .await
+    .and_then(identity);
+    if let Err(error) = result {
+        let on_error = cmd.on_error().unwrap_or(ctx.on_error);
+        if let Err(error) = catch_panic(on_error(name.clone(), cmd_ctx.clone(), error)).await {
+            tracing::error!(?error, "error handler for '{}' failed", name);
+        }
+        if !cmd_ctx.is_acknowledged().await {
+            let data = failure_response("Something went wrong while running this command.");
+            cmd_ctx.respond(data).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn handle_autocomplete(
+    ctx: &EventDispatchContext,
+    interaction: Interaction,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let Some(InteractionData::ApplicationCommand(data)) = &interaction.data else {
+        return Err(format!("interaction {} has no command data", interaction.id).into());
+    };
+
+    let (name, options) = command_path(data);
+
+    let focused = options.iter().find_map(|o| match &o.value {
+        CommandOptionValue::Focused(value, _) => Some((o.name.clone(), value.clone())),
+        _ => None,
+    });
+    let Some((parameter, value)) = focused else {
+        tracing::warn!("No focused option in autocomplete for '{}'", name);
+        return Ok(());
+    };
+
+    let Some(autocomplete) = ctx.commands.get_autocomplete(&name, &parameter) else {
+        tracing::warn!("Could not find autocomplete: '{}' '{}'", name, parameter);
+        return Ok(());
+    };
+
+    let args = collect_arguments(&name, options, data.resolved.as_ref());
+    let interaction_id = interaction.id;
+    let token = interaction.token.clone();
+    let autocomplete_ctx = AutocompleteContext {
+        state: ctx.state.clone(),
+        client: ctx.client.clone(),
+        interaction,
+    };
+
+    let choices = match catch_panic(autocomplete(autocomplete_ctx, value, args)).await {
+        Ok(choices) => choices,
+        Err(error) => {
+            tracing::error!(?error, "autocomplete '{}' '{}' failed", name, parameter);
+            Vec::new()
+        }
+    };
+    let response = InteractionResponse {
+        kind: InteractionResponseType::ApplicationCommandAutocompleteResult,
+        data: Some(
+            InteractionResponseDataBuilder::new()
+                .choices(choices.iter().take(25).map(|c| c.create_twilight_choice()))
+                .build(),
+        ),
+    };
+    ctx.client
+        .http
+        .interaction(ctx.client.application_id)
+        .create_response(interaction_id, &token, &response)
+        .await?;
+
+    Ok(())
+}
+
+async fn handle_component(
+    ctx: &EventDispatchContext,
+    interaction: Interaction,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let Some(InteractionData::MessageComponent(data)) = &interaction.data else {
+        return Err(format!("interaction {} has no component data", interaction.id).into());
+    };
+
+    let (prefix, args) = CustomId::parse(&data.custom_id);
+
+    if let Some(component) = ctx.components.get(&prefix) {
+        let component_ctx =
+            ComponentContext::new(ctx.state.clone(), ctx.client.clone(), interaction);
+        if let Err(error) = catch_panic(component(component_ctx.clone(), args))
+            .await
+            .and_then(identity)
+        {
+            let on_error = (ctx.on_component_error)(prefix.clone(), component_ctx.clone(), error);
+            if let Err(error) = catch_panic(on_error).await {
+                tracing::error!(?error, "error handler for component '{}' failed", prefix);
+            }
+            if !component_ctx.is_acknowledged() {
+                let data =
+                    failure_response("Something went wrong while handling this interaction.");
+                component_ctx.respond(data).await?;
+            }
+        }
+    } else {
+        tracing::warn!("Could not find component: '{}'", prefix)
+    }
+    Ok(())
+}
+
+async fn handle_modal(
+    ctx: &EventDispatchContext,
+    interaction: Interaction,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let Some(InteractionData::ModalSubmit(data)) = &interaction.data else {
+        return Err(format!("interaction {} has no modal data", interaction.id).into());
+    };
+
+    let (prefix, args) = CustomId::parse(&data.custom_id);
+
+    if let Some(modal) = ctx.modals.get(&prefix) {
+        let modal_ctx = ModalContext::new(ctx.state.clone(), ctx.client.clone(), interaction);
+        if let Err(error) = catch_panic(modal(modal_ctx.clone(), args))
+            .await
+            .and_then(identity)
+        {
+            let on_error = (ctx.on_modal_error)(prefix.clone(), modal_ctx.clone(), error);
+            if let Err(error) = catch_panic(on_error).await {
+                tracing::error!(?error, "error handler for modal '{}' failed", prefix);
+            }
+            if !modal_ctx.is_acknowledged() {
+                let data =
+                    failure_response("Something went wrong while handling this interaction.");
+                modal_ctx.respond(data).await?;
+            }
+        }
+    } else {
+        tracing::warn!("Could not find modal: '{}'", prefix)
+    }
+    Ok(())
+}
+
+fn failure_response(content: &str) -> InteractionResponseData {
+    InteractionResponseDataBuilder::new()
+        .content(content)
+        .flags(MessageFlags::EPHEMERAL)
+        .build()
+}
+
+fn command_path(data: &CommandData) -> (String, &Vec<CommandDataOption>) {
     let mut name = String::from(&data.name);
     let mut options = &data.options;
-    tracing::debug!("{:#?}", data);
     while options.len() == 1 {
         match &options[0].value {
             CommandOptionValue::SubCommand(o) | CommandOptionValue::SubCommandGroup(o) => {
                 name.push(' ');
                 name.push_str(&options[0].name);
-                options = &o;
+                options = o;
             }
             _ => break,
         }
     }
+    (name, options)
+}
 
+fn collect_arguments(
+    name: &str,
+    options: &[CommandDataOption],
+    resolved: Option<&CommandInteractionDataResolved>,
+) -> HashMap<String, Argument> {
     let mut args = HashMap::new();
     for o in options {
-        args.insert(o.name.clone(), Argument::try_from(&o.value).unwrap());
-    }
-
-    if let Some(cmd) = ctx.commands.get(&name) {
-        let cmd_ctx = CommandContext {
-            state: ctx.state.clone(),
-            client: ctx.client.clone(),
-            interaction,
-        };
-        let result = cmd.run(cmd_ctx, args).await;
-    } else {
-        tracing::warn!("Could not find command: '{}'", name)
+        if let CommandOptionValue::Focused(..) = o.value {
+            continue;
+        }
+        match Argument::resolve(&o.value, resolved) {
+            Some(arg) => {
+                args.insert(o.name.clone(), arg);
+            }
+            None => tracing::warn!("Unsupported value for option '{}' in '{}'", o.name, name),
+        }
     }
-    Ok(())
+    args
 }
 
 pub struct BotBuilder<'a> {
     groups: Vec<&'a Group>,
+    scope: CommandScope,
+    cleanup: Vec<RegistrationTarget>,
+    registration_record: Option<PathBuf>,
+    dry_run: bool,
+    on_error: ErrorHandler,
+    on_component_error: ComponentErrorHandler,
+    on_modal_error: ModalErrorHandler,
+    localizations: Option<Localizations>,
+    prefix: Option<String>,
+    guild_prefixes: HashMap<Id<GuildMarker>, String>,
 }
 
 impl<'a> BotBuilder<'a> {
     fn new() -> Self {
-        Self { groups: Vec::new() }
+        Self {
+            groups: Vec::new(),
+            scope: CommandScope::Global,
+            cleanup: Vec::new(),
+            registration_record: None,
+            dry_run: false,
+            on_error: error::default_error_handler,
+            on_component_error: error::default_component_error_handler,
+            on_modal_error: error::default_modal_error_handler,
+            localizations: None,
+            prefix: None,
+            guild_prefixes: HashMap::new(),
+        }
     }
     pub fn group(mut self, group: &'a Group) -> Self {
         self.groups.push(group);
@@ -214,7 +668,106 @@ impl<'a> BotBuilder<'a> {
         self.groups = groups;
         self
     }
+    pub fn scope(mut self, scope: CommandScope) -> Self {
+        self.scope = scope;
+        self
+    }
+    pub fn guilds(mut self, guilds: Vec<Id<GuildMarker>>) -> Self {
+        self.scope = CommandScope::Guilds(guilds);
+        self
+    }
+    pub fn cleanup(mut self, target: RegistrationTarget) -> Self {
+        self.cleanup.push(target);
+        self
+    }
+    pub fn registration_record(mut self, path: impl Into<PathBuf>) -> Self {
+        self.registration_record = Some(path.into());
+        self
+    }
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+    pub fn on_error(mut self, on_error: ErrorHandler) -> Self {
+        self.on_error = on_error;
+        self
+    }
+    pub fn on_component_error(mut self, on_error: ComponentErrorHandler) -> Self {
+        self.on_component_error = on_error;
+        self
+    }
+    pub fn on_modal_error(mut self, on_error: ModalErrorHandler) -> Self {
+        self.on_modal_error = on_error;
+        self
+    }
+    pub fn localizations(mut self, localizations: Localizations) -> Self {
+        self.localizations = Some(localizations);
+        self
+    }
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+    pub fn guild_prefix(mut self, guild_id: Id<GuildMarker>, prefix: impl Into<String>) -> Self {
+        self.guild_prefixes.insert(guild_id, prefix.into());
+        self
+    }
     pub fn build(self) -> Bot {
-        Bot::new(&self.groups)
+        Bot::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BoxedFuture, CommandResult, Component, ModalHandler};
+
+    fn noop_component(_ctx: ComponentContext, _args: Vec<String>) -> BoxedFuture<CommandResult> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn components() -> Vec<Component> {
+        ["vote", "vote", "poll"]
+            .into_iter()
+            .map(|prefix| Component {
+                prefix: String::from(prefix),
+                function: noop_component,
+            })
+            .collect()
+    }
+
+    fn noop_modal(_ctx: ModalContext, _args: Vec<String>) -> BoxedFuture<CommandResult> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn modals() -> Vec<ModalHandler> {
+        ["report", "report"]
+            .into_iter()
+            .map(|prefix| ModalHandler {
+                prefix: String::from(prefix),
+                function: noop_modal,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_duplicate_prefixes() {
+        let group = Group {
+            build_commands: Vec::new,
+            build_command_groups: Vec::new,
+            build_events: Vec::new,
+            build_components: components,
+            build_modals: modals,
+            setup: None,
+        };
+        let bot = Bot::builder().group(&group).build();
+        let errors: Vec<String> = bot.validate().iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "'report': duplicate modal prefix",
+                "'vote': duplicate component prefix",
+            ]
+        );
     }
 }
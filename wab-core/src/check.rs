@@ -0,0 +1,17 @@
+use crate::{BoxedFuture, CommandContext};
+
+pub type CheckFunction = fn(CommandContext) -> BoxedFuture<bool>;
+
+#[derive(Clone, Debug)]
+pub struct Check {
+    pub name: String,
+    pub function: CheckFunction,
+}
+
+pub fn guild_only(ctx: CommandContext) -> BoxedFuture<bool> {
+    Box::pin(async move { ctx.guild_id().is_some() })
+}
+
+pub fn dm_only(ctx: CommandContext) -> BoxedFuture<bool> {
+    Box::pin(async move { ctx.guild_id().is_none() })
+}
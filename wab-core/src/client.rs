@@ -4,9 +4,7 @@ use twilight_model::id::{marker::ApplicationMarker, Id};
 pub struct Client {
     pub http: twilight_http::Client,
     pub cache: InMemoryCache,
-    pub application_id: Id<ApplicationMarker>
+    pub application_id: Id<ApplicationMarker>,
 }
 
-impl Client {
-    
-}
\ No newline at end of file
+impl Client {}
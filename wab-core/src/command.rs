@@ -1,27 +1,94 @@
 use std::{collections::HashMap, future::Future, pin::Pin};
 
-use twilight_model::application::command::CommandOption;
+use twilight_model::application::command::{CommandOption, CommandType};
+use twilight_model::guild::Permissions;
 
 use super::argument::Argument;
+use super::check::Check;
 use super::context::CommandContext;
-use super::parameter::Parameter;
+use super::cooldown::Cooldown;
+use super::error::{Error, ErrorHandler};
+use super::parameter::{Parameter, ParameterError};
+use super::scope::CommandScope;
 
-pub type CommandResult = Result<(), ()>;
+pub type CommandResult = Result<(), Error>;
 
 pub type BoxedFuture<T> = Pin<Box<dyn Future<Output = T> + Send + 'static>>;
-pub type CommandFunction = fn(CommandContext, HashMap<String, Argument>) -> BoxedFuture<CommandResult>;
+pub type CommandFunction =
+    fn(CommandContext, HashMap<String, Argument>) -> BoxedFuture<CommandResult>;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CommandPermissions {
+    pub default_member_permissions: Option<Permissions>,
+    pub dm_permission: Option<bool>,
+    pub nsfw: Option<bool>,
+}
+impl CommandPermissions {
+    pub fn or(self, other: Self) -> Self {
+        Self {
+            default_member_permissions: self
+                .default_member_permissions
+                .or(other.default_member_permissions),
+            dm_permission: self.dm_permission.or(other.dm_permission),
+            nsfw: self.nsfw.or(other.nsfw),
+        }
+    }
+    pub fn conflicts_with(&self, other: &Self) -> bool {
+        fn conflict<T: PartialEq>(a: &Option<T>, b: &Option<T>) -> bool {
+            matches!((a, b), (Some(a), Some(b)) if a != b)
+        }
+        conflict(
+            &self.default_member_permissions,
+            &other.default_member_permissions,
+        ) || conflict(&self.dm_permission, &other.dm_permission)
+            || conflict(&self.nsfw, &other.nsfw)
+    }
+}
+
+pub const TARGET_ARGUMENT: &str = "target";
+
+#[derive(Clone, Debug)]
+pub struct CommandGroup {
+    pub name: String,
+    pub description: String,
+    pub name_localizations: Option<HashMap<String, String>>,
+    pub description_localizations: Option<HashMap<String, String>>,
+    pub permissions: CommandPermissions,
+}
 
 #[derive(Debug)]
 pub struct Command {
+    kind: CommandType,
     name: String,
     description: String,
     parameters: Vec<Parameter>,
+    scope: Option<CommandScope>,
+    on_error: Option<ErrorHandler>,
+    checks: Vec<Check>,
+    cooldown: Option<Cooldown>,
+    permissions: CommandPermissions,
     function: CommandFunction,
+    errors: Vec<ParameterError>,
 }
 impl Command {
-    pub fn run(&self, ctx: CommandContext, args: HashMap<String, Argument>) -> BoxedFuture<CommandResult> {
+    pub async fn check(&self, ctx: &CommandContext) -> CommandResult {
+        for check in self.checks.iter() {
+            if !(check.function)(ctx.clone()).await {
+                return Err(Error::check_failed(check.name.as_str()));
+            }
+        }
+        Ok(())
+    }
+    pub fn run(
+        &self,
+        ctx: CommandContext,
+        args: HashMap<String, Argument>,
+    ) -> BoxedFuture<CommandResult> {
         (self.function)(ctx, args)
     }
+    pub fn kind(&self) -> CommandType {
+        self.kind
+    }
     pub fn name(&self) -> &str {
         self.name.as_str()
     }
@@ -31,14 +98,35 @@ impl Command {
     pub fn parameters(&self) -> &Vec<Parameter> {
         &self.parameters
     }
+    pub fn parameter(&self, name: &str) -> Option<&Parameter> {
+        self.parameters.iter().find(|p| p.name() == name)
+    }
+    pub fn scope(&self) -> Option<&CommandScope> {
+        self.scope.as_ref()
+    }
+    pub fn on_error(&self) -> Option<ErrorHandler> {
+        self.on_error
+    }
+    pub fn checks(&self) -> &Vec<Check> {
+        &self.checks
+    }
+    pub fn cooldown(&self) -> Option<&Cooldown> {
+        self.cooldown.as_ref()
+    }
+    pub fn permissions(&self) -> &CommandPermissions {
+        &self.permissions
+    }
+    pub fn errors(&self) -> &[ParameterError] {
+        &self.errors
+    }
     pub fn create_twilight_command_options(&self) -> Vec<CommandOption> {
         self.parameters
             .iter()
             .map(|p| CommandOption {
-                autocomplete: None,
-                channel_types: None,
+                autocomplete: p.autocomplete().map(|_| true),
+                channel_types: p.create_twilight_channel_types(),
                 choices: p.create_twilight_choices(),
-                description: String::from(p.description()),
+                description: p.create_twilight_description(),
                 description_localizations: None,
                 kind: p.kind().create_twilight_option_type(),
                 max_length: p.create_twilight_max_length(),
@@ -48,7 +136,7 @@ impl Command {
                 name: String::from(p.name()),
                 name_localizations: None,
                 options: None,
-                required: Some(p.required().clone()),
+                required: Some(*p.required()),
             })
             .collect()
     }
@@ -56,18 +144,46 @@ impl Command {
         CommandBuilder::new()
     }
 }
-#[derive(Default)]
 pub struct CommandBuilder {
+    kind: CommandType,
     name: String,
     category: String,
     description: String,
     parameters: Vec<Parameter>,
+    scope: Option<CommandScope>,
+    on_error: Option<ErrorHandler>,
+    checks: Vec<Check>,
+    cooldown: Option<Cooldown>,
+    permissions: CommandPermissions,
     function: Option<CommandFunction>,
+    errors: Vec<ParameterError>,
+}
+impl Default for CommandBuilder {
+    fn default() -> Self {
+        Self {
+            kind: CommandType::ChatInput,
+            name: String::new(),
+            category: String::new(),
+            description: String::new(),
+            parameters: Vec::new(),
+            scope: None,
+            on_error: None,
+            checks: Vec::new(),
+            cooldown: None,
+            permissions: CommandPermissions::default(),
+            function: None,
+            errors: Vec::new(),
+        }
+    }
 }
 impl CommandBuilder {
     fn new() -> Self {
         Self::default()
     }
+    pub fn kind(mut self, kind: CommandType) -> Self {
+        self.kind = kind;
+        self
+    }
     pub fn name(mut self, name: impl Into<String>) -> Self {
         self.name = name.into();
         self
@@ -84,23 +200,74 @@ impl CommandBuilder {
         self.parameters.push(option);
         self
     }
+    pub fn try_parameter(mut self, option: Result<Parameter, ParameterError>) -> Self {
+        match option {
+            Ok(option) => self.parameters.push(option),
+            Err(error) => self.errors.push(error),
+        }
+        self
+    }
     pub fn parameters(mut self, options: Vec<Parameter>) -> Self {
         self.parameters = options;
         self
     }
+    pub fn scope(mut self, scope: CommandScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+    pub fn default_scope(mut self, scope: CommandScope) -> Self {
+        self.scope.get_or_insert(scope);
+        self
+    }
+    pub fn on_error(mut self, on_error: ErrorHandler) -> Self {
+        self.on_error = Some(on_error);
+        self
+    }
+    pub fn check(mut self, check: Check) -> Self {
+        self.checks.push(check);
+        self
+    }
+    pub fn group_checks(mut self, checks: Vec<Check>) -> Self {
+        self.checks.splice(0..0, checks);
+        self
+    }
+    pub fn cooldown(mut self, cooldown: Cooldown) -> Self {
+        self.cooldown = Some(cooldown);
+        self
+    }
+    pub fn default_member_permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions.default_member_permissions = Some(permissions);
+        self
+    }
+    pub fn dm_permission(mut self, dm_permission: bool) -> Self {
+        self.permissions.dm_permission = Some(dm_permission);
+        self
+    }
+    pub fn nsfw(mut self, nsfw: bool) -> Self {
+        self.permissions.nsfw = Some(nsfw);
+        self
+    }
+    pub fn default_permissions(mut self, permissions: CommandPermissions) -> Self {
+        self.permissions = self.permissions.or(permissions);
+        self
+    }
     pub fn function(mut self, function: CommandFunction) -> Self {
         self.function = Some(function);
         self
     }
     pub fn build(self) -> Command {
-        assert!(!self.name.is_empty() && self.name.len() <= 32);
-        assert!(self.name.chars().filter(|c| c == &' ').count() <= 2);
-        assert!(!self.description.is_empty() && self.description.len() <= 100);
         Command {
+            kind: self.kind,
             name: self.name,
             description: self.description,
             parameters: self.parameters,
+            scope: self.scope,
+            on_error: self.on_error,
+            checks: self.checks,
+            cooldown: self.cooldown,
+            permissions: self.permissions,
             function: self.function.unwrap(),
+            errors: self.errors,
         }
     }
 }
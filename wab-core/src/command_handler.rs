@@ -1,4 +1,9 @@
-use crate::Command;
+use crate::localization::{DEFAULT_SUFFIX, DEFAULT_SUFFIX_KEY};
+use crate::validation::{validate_application_commands, validate_parameter, ValidationError};
+use crate::{
+    AutocompleteFunction, Command, CommandGroup, CommandPermissions, CommandScope, Localizations,
+    RegistrationTarget,
+};
 use std::collections::{hash_map::Entry, HashMap, HashSet};
 use twilight_model::application::command::{
     Command as ApplicationCommand, CommandOption, CommandOptionType, CommandType,
@@ -7,68 +12,310 @@ use twilight_model::id::Id;
 
 pub struct CommandHandler {
     commands: HashMap<String, Command>,
-    command_adjacency: HashMap<String, HashSet<String>>,
+    context_commands: HashMap<(CommandType, String), Command>,
+    command_groups: HashMap<String, CommandGroup>,
+    root_permissions: HashMap<String, CommandPermissions>,
+    errors: Vec<ValidationError>,
 }
 impl CommandHandler {
-    pub fn new(commands: Vec<Command>) -> Self {
+    pub fn new(commands: Vec<Command>, command_groups: Vec<CommandGroup>) -> Self {
         let mut command_map = HashMap::new();
+        let mut context_commands = HashMap::new();
+        let mut root_permissions: HashMap<String, (CommandPermissions, String)> = HashMap::new();
+        let mut errors = Vec::new();
         for command in commands {
+            for error in command.errors() {
+                errors.push(ValidationError::new(
+                    format!("{} {}", command.name(), error.name()),
+                    "parameter kind must be set",
+                ));
+            }
+            if command.kind() != CommandType::ChatInput {
+                match context_commands.entry((command.kind(), String::from(command.name()))) {
+                    Entry::Occupied(o) => errors.push(ValidationError::new(
+                        &o.key().1,
+                        format!("duplicate {:?} command name", o.key().0),
+                    )),
+                    Entry::Vacant(v) => {
+                        v.insert(command);
+                    }
+                };
+                continue;
+            }
             let name = String::from(command.name());
             match command_map.entry(name) {
-                Entry::Occupied(o) => panic!("Duplicate command name: '{}'", o.key()),
-                Entry::Vacant(v) => v.insert(command),
+                Entry::Occupied(o) => {
+                    errors.push(ValidationError::new(o.key(), "duplicate command name"))
+                }
+                Entry::Vacant(v) => {
+                    merge_root_permissions(
+                        &mut root_permissions,
+                        v.key(),
+                        *command.permissions(),
+                        &mut errors,
+                    );
+                    v.insert(command);
+                }
             };
         }
 
-        let mut command_adjacency = HashMap::new();
-
-        for name in command_map.keys() {
-            let mut indices: Vec<usize> = name
-                .chars()
-                .enumerate()
-                .filter(|(_, c)| c == &' ')
-                .map(|(i, _)| i)
-                .collect();
-            indices.push(name.len());
-
-            for i in 0..indices.len() {
-                let set = command_adjacency
-                    .entry(String::from(&name[..indices[i]]))
-                    .or_insert_with(|| HashSet::new());
-
-                if i != indices.len() - 1 {
-                    set.insert(String::from(&name[..indices[i + 1]]));
+        let mut group_map = HashMap::new();
+        for group in command_groups {
+            if command_map.contains_key(&group.name) {
+                errors.push(ValidationError::new(
+                    &group.name,
+                    "cannot be both a command and a command group",
+                ));
+                continue;
+            }
+            if group.name.contains(' ') && group.permissions != CommandPermissions::default() {
+                errors.push(ValidationError::new(
+                    &group.name,
+                    "subgroups cannot set permissions, Discord only allows them on roots",
+                ));
+            }
+            match group_map.entry(group.name.clone()) {
+                Entry::Occupied(o) => {
+                    errors.push(ValidationError::new(o.key(), "duplicate command group"))
+                }
+                Entry::Vacant(v) => {
+                    merge_root_permissions(
+                        &mut root_permissions,
+                        v.key(),
+                        group.permissions,
+                        &mut errors,
+                    );
+                    v.insert(group);
                 }
+            };
+        }
+
+        let adjacency = build_adjacency(command_map.keys());
+        for name in adjacency.keys() {
+            if !command_map.contains_key(name) && !group_map.contains_key(name) {
+                tracing::warn!("Command group '{}' has no declaration or description", name);
+            }
+        }
+        for name in group_map.keys() {
+            if !adjacency.contains_key(name) {
+                tracing::warn!("Command group '{}' has no commands", name);
             }
         }
 
         Self {
             commands: command_map,
-            command_adjacency,
+            context_commands,
+            command_groups: group_map,
+            root_permissions: root_permissions
+                .into_iter()
+                .map(|(root, (permissions, _))| (root, permissions))
+                .collect(),
+            errors,
         }
     }
     pub fn get(&self, name: &str) -> Option<&Command> {
         self.commands.get(name)
     }
-    pub fn create_application_commands(&self) -> Vec<ApplicationCommand> {
+    pub fn find_prefixed<'a>(&self, text: &'a str) -> Option<(&Command, &'a str)> {
+        let mut name = String::new();
+        let mut rest = text;
+        let mut found = None;
+        for _ in 0..3 {
+            let trimmed = rest.trim_start();
+            let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+            if end == 0 {
+                break;
+            }
+            if !name.is_empty() {
+                name.push(' ');
+            }
+            name.push_str(&trimmed[..end].to_lowercase());
+            rest = &trimmed[end..];
+            if let Some(command) = self.commands.get(&name) {
+                found = Some((command, rest));
+            }
+        }
+        found
+    }
+    pub fn permissions(&self, name: &str) -> CommandPermissions {
+        let root = name.split(' ').next().unwrap_or(name);
+        self.root_permissions.get(root).copied().unwrap_or_default()
+    }
+    fn group_description(&self, name: &str) -> String {
+        match self.command_groups.get(name) {
+            Some(group) => group.description.clone(),
+            None => String::from("(command group)"),
+        }
+    }
+    fn group_localizations(
+        &self,
+        name: &str,
+        localizations: impl Fn(&CommandGroup) -> &Option<HashMap<String, String>>,
+    ) -> Option<HashMap<String, String>> {
+        self.command_groups
+            .get(name)
+            .and_then(|group| localizations(group).clone())
+    }
+    pub fn get_context(&self, kind: CommandType, name: &str) -> Option<&Command> {
+        self.context_commands.get(&(kind, String::from(name)))
+    }
+    pub fn get_autocomplete(&self, name: &str, parameter: &str) -> Option<AutocompleteFunction> {
+        self.commands
+            .get(name)
+            .and_then(|c| c.parameter(parameter))
+            .and_then(|p| p.autocomplete())
+    }
+    pub fn create_application_commands(
+        &self,
+        default_scope: &CommandScope,
+    ) -> HashMap<RegistrationTarget, Vec<ApplicationCommand>> {
+        let mut targets: HashMap<RegistrationTarget, Vec<&String>> = HashMap::new();
+        for (name, command) in self.commands.iter() {
+            for target in command.scope().unwrap_or(default_scope).targets() {
+                targets.entry(target).or_default().push(name);
+            }
+        }
+
+        let mut payloads: HashMap<RegistrationTarget, Vec<ApplicationCommand>> = targets
+            .into_iter()
+            .map(|(target, names)| {
+                let adjacency = build_adjacency(names.into_iter());
+                (target, self.create_scoped_application_commands(&adjacency))
+            })
+            .collect();
+
+        let mut context_commands: Vec<&Command> = self.context_commands.values().collect();
+        context_commands.sort_by_key(|c| (c.kind() == CommandType::Message, c.name()));
+        for command in context_commands {
+            for target in command.scope().unwrap_or(default_scope).targets() {
+                payloads
+                    .entry(target)
+                    .or_default()
+                    .push(create_context_application_command(command));
+            }
+        }
+
+        payloads
+    }
+    pub fn localize(&self, command: &mut ApplicationCommand, localizations: &Localizations) {
+        localizations.apply(command);
+        if command.kind == CommandType::ChatInput {
+            self.localize_defaults(&command.name, &mut command.options, localizations);
+        }
+    }
+    fn localize_defaults(
+        &self,
+        path: &str,
+        options: &mut [CommandOption],
+        localizations: &Localizations,
+    ) {
+        for option in options.iter_mut() {
+            match option.kind {
+                CommandOptionType::SubCommand | CommandOptionType::SubCommandGroup => {
+                    let path = format!("{path} {}", option.name);
+                    let options = option.options.as_deref_mut().unwrap_or_default();
+                    self.localize_defaults(&path, options, localizations);
+                }
+                _ => {
+                    let parameter = self
+                        .commands
+                        .get(path)
+                        .and_then(|command| command.parameter(&option.name));
+                    let Some(label) = parameter.and_then(|p| p.default_label()) else {
+                        continue;
+                    };
+                    let choice = option.choices.iter().flatten().find(|c| c.name == label);
+                    let descriptions = option.description_localizations.iter_mut().flatten();
+                    for (locale, description) in descriptions {
+                        let label = choice
+                            .and_then(|c| c.name_localizations.as_ref()?.get(locale))
+                            .map_or(label, String::as_str);
+                        description.push_str(&localizations.translate_or(
+                            &[locale],
+                            DEFAULT_SUFFIX_KEY,
+                            DEFAULT_SUFFIX,
+                            &[("value", &label)],
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    pub fn validate(
+        &self,
+        default_scope: &CommandScope,
+        localizations: Option<&Localizations>,
+    ) -> Vec<ValidationError> {
+        let mut errors = self.errors.clone();
+
+        for (name, command) in self.commands.iter() {
+            for parameter in command.parameters() {
+                let path = format!("{name} {}", parameter.name());
+                validate_parameter(&path, parameter, &mut errors);
+                if parameter.default_value().is_some()
+                    && !parameter.choices().is_empty()
+                    && parameter.default_choice().is_none()
+                {
+                    errors.push(ValidationError::new(
+                        path,
+                        "default must be one of the choices",
+                    ));
+                }
+            }
+        }
+        for name in self.commands.keys() {
+            if name.matches(' ').count() > 2 {
+                errors.push(ValidationError::new(
+                    name,
+                    "command names may have at most 3 parts",
+                ));
+            }
+            for (i, _) in name.match_indices(' ') {
+                if self.commands.contains_key(&name[..i]) {
+                    errors.push(ValidationError::new(
+                        &name[..i],
+                        format!("cannot be both a command and a group containing '{name}'"),
+                    ));
+                }
+            }
+        }
+
+        for application_commands in self.create_application_commands(default_scope).values_mut() {
+            if let Some(localizations) = localizations {
+                for command in application_commands.iter_mut() {
+                    self.localize(command, localizations);
+                }
+            }
+            errors.extend(validate_application_commands(application_commands));
+        }
+
+        errors.sort();
+        errors.dedup();
+        errors
+    }
+    fn create_scoped_application_commands(
+        &self,
+        command_adjacency: &HashMap<String, HashSet<String>>,
+    ) -> Vec<ApplicationCommand> {
         let mut vec = Vec::new();
-        for (root, depth_2_cmds) in self
-            .command_adjacency
+        for (root, depth_2_cmds) in command_adjacency
             .iter()
             .filter(|(name, _)| name.find(' ').is_none())
         {
+            let permissions = self.root_permissions.get(root).copied().unwrap_or_default();
             let mut cmd = ApplicationCommand {
                 application_id: None,
-                default_member_permissions: None,
-                dm_permission: None,
-                description: String::from("(command group)"),
-                description_localizations: None,
+                default_member_permissions: permissions.default_member_permissions,
+                dm_permission: permissions.dm_permission,
+                description: self.group_description(root),
+                description_localizations: self
+                    .group_localizations(root, |g| &g.description_localizations),
                 guild_id: None,
                 id: None,
                 kind: CommandType::ChatInput,
                 name: String::from(root),
-                name_localizations: None,
-                nsfw: None,
+                name_localizations: self.group_localizations(root, |g| &g.name_localizations),
+                nsfw: permissions.nsfw,
                 options: Vec::new(),
                 version: Id::new(1),
             };
@@ -79,20 +326,24 @@ impl CommandHandler {
                 continue;
             }
 
+            let mut depth_2_cmds: Vec<&String> = depth_2_cmds.iter().collect();
+            depth_2_cmds.sort();
             for depth_2_name in depth_2_cmds {
                 let mut sub = CommandOption {
                     autocomplete: None,
                     channel_types: None,
                     choices: None,
-                    description: String::from("(command group)"),
-                    description_localizations: None,
+                    description: self.group_description(depth_2_name),
+                    description_localizations: self
+                        .group_localizations(depth_2_name, |g| &g.description_localizations),
                     kind: CommandOptionType::SubCommandGroup,
                     max_length: None,
                     max_value: None,
                     min_length: None,
                     min_value: None,
                     name: String::from(&depth_2_name[(depth_2_name.find(' ').unwrap() + 1)..]),
-                    name_localizations: None,
+                    name_localizations: self
+                        .group_localizations(depth_2_name, |g| &g.name_localizations),
                     options: None,
                     required: None,
                 };
@@ -105,8 +356,16 @@ impl CommandHandler {
                 }
 
                 let mut options = Vec::new();
-                for depth_3_name in self.command_adjacency.get(depth_2_name).unwrap() {
-                    let c = self.commands.get(depth_3_name).unwrap();
+                let mut depth_3_cmds: Vec<&String> = command_adjacency
+                    .get(depth_2_name)
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                depth_3_cmds.sort();
+                for depth_3_name in depth_3_cmds {
+                    let Some(c) = self.commands.get(depth_3_name) else {
+                        continue;
+                    };
                     options.push(CommandOption {
                         autocomplete: None,
                         channel_types: None,
@@ -132,3 +391,441 @@ impl CommandHandler {
         vec
     }
 }
+
+fn merge_root_permissions(
+    root_permissions: &mut HashMap<String, (CommandPermissions, String)>,
+    name: &str,
+    permissions: CommandPermissions,
+    errors: &mut Vec<ValidationError>,
+) {
+    let root = String::from(name.split(' ').next().unwrap());
+    match root_permissions.entry(root) {
+        Entry::Occupied(mut o) => {
+            let (existing, other) = o.get();
+            if existing.conflicts_with(&permissions) {
+                errors.push(ValidationError::new(
+                    o.key(),
+                    format!("conflicting permissions: '{other}' and '{name}' disagree"),
+                ));
+                return;
+            }
+            let merged = existing.or(permissions);
+            o.get_mut().0 = merged;
+        }
+        Entry::Vacant(v) => {
+            v.insert((permissions, String::from(name)));
+        }
+    }
+}
+
+fn create_context_application_command(command: &Command) -> ApplicationCommand {
+    let permissions = command.permissions();
+    ApplicationCommand {
+        application_id: None,
+        default_member_permissions: permissions.default_member_permissions,
+        dm_permission: permissions.dm_permission,
+        description: String::new(),
+        description_localizations: None,
+        guild_id: None,
+        id: None,
+        kind: command.kind(),
+        name: String::from(command.name()),
+        name_localizations: None,
+        nsfw: permissions.nsfw,
+        options: Vec::new(),
+        version: Id::new(1),
+    }
+}
+
+fn build_adjacency<'a>(
+    names: impl Iterator<Item = &'a String>,
+) -> HashMap<String, HashSet<String>> {
+    let mut command_adjacency = HashMap::new();
+
+    for name in names {
+        let mut indices: Vec<usize> = name.match_indices(' ').map(|(i, _)| i).collect();
+        indices.push(name.len());
+
+        for i in 0..indices.len() {
+            let set = command_adjacency
+                .entry(String::from(&name[..indices[i]]))
+                .or_insert_with(HashSet::new);
+
+            if i != indices.len() - 1 {
+                set.insert(String::from(&name[..indices[i + 1]]));
+            }
+        }
+    }
+
+    command_adjacency
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        BoxedFuture, CommandContext, CommandResult, Parameter, ParameterChoice,
+        ParameterChoiceType, ParameterType,
+    };
+    use twilight_model::guild::Permissions;
+
+    fn noop(
+        _ctx: CommandContext,
+        _args: HashMap<String, crate::Argument>,
+    ) -> BoxedFuture<CommandResult> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn command(name: &str) -> Command {
+        Command::builder()
+            .name(name)
+            .description("a command")
+            .function(noop)
+            .build()
+    }
+
+    fn group(name: &str, permissions: CommandPermissions) -> CommandGroup {
+        CommandGroup {
+            name: String::from(name),
+            description: String::from("a group"),
+            name_localizations: None,
+            description_localizations: None,
+            permissions,
+        }
+    }
+
+    fn messages(errors: Vec<ValidationError>) -> Vec<String> {
+        errors.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn valid_tree_has_no_errors() {
+        let handler = CommandHandler::new(
+            vec![
+                command("ping"),
+                command("admin ban"),
+                command("admin role add"),
+            ],
+            vec![group("admin", CommandPermissions::default())],
+        );
+        assert!(handler.validate(&CommandScope::Global, None).is_empty());
+        let payloads = handler.create_application_commands(&CommandScope::Global);
+        assert_eq!(payloads[&RegistrationTarget::Global].len(), 2);
+    }
+
+    #[test]
+    fn reports_duplicates_instead_of_panicking() {
+        let user_command = || {
+            Command::builder()
+                .kind(CommandType::User)
+                .name("Info")
+                .function(noop)
+                .build()
+        };
+        let handler = CommandHandler::new(
+            vec![
+                command("ping"),
+                command("ping"),
+                user_command(),
+                user_command(),
+            ],
+            vec![
+                group("admin", CommandPermissions::default()),
+                group("admin", CommandPermissions::default()),
+                group("ping", CommandPermissions::default()),
+            ],
+        );
+        assert_eq!(
+            messages(handler.validate(&CommandScope::Global, None)),
+            vec![
+                "'Info': duplicate User command name",
+                "'admin': duplicate command group",
+                "'ping': cannot be both a command and a command group",
+                "'ping': duplicate command name",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_command_that_is_also_a_group() {
+        let handler = CommandHandler::new(vec![command("tag"), command("tag add")], Vec::new());
+        assert_eq!(
+            messages(handler.validate(&CommandScope::Global, None)),
+            vec!["'tag': cannot be both a command and a group containing 'tag add'"]
+        );
+    }
+
+    #[test]
+    fn validates_names_by_part_and_character() {
+        let long = "ä".repeat(32);
+        let handler = CommandHandler::new(
+            vec![
+                command(&format!("{long} {long} {long}")),
+                command("a b c d"),
+            ],
+            Vec::new(),
+        );
+        assert_eq!(
+            messages(handler.validate(&CommandScope::Global, None)),
+            vec!["'a b c d': command names may have at most 3 parts"]
+        );
+    }
+
+    #[test]
+    fn reports_subgroup_permissions() {
+        let permissions = CommandPermissions {
+            default_member_permissions: Some(Permissions::BAN_MEMBERS),
+            ..Default::default()
+        };
+        let handler = CommandHandler::new(
+            vec![command("admin role add")],
+            vec![group("admin role", permissions)],
+        );
+        assert_eq!(
+            messages(handler.validate(&CommandScope::Global, None)),
+            vec![
+                "'admin role': subgroups cannot set permissions, Discord only allows them on roots"
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_conflicting_root_permissions() {
+        let ban = Command::builder()
+            .name("mod ban")
+            .description("ban a user")
+            .default_member_permissions(Permissions::BAN_MEMBERS)
+            .function(noop)
+            .build();
+        let kick = Command::builder()
+            .name("mod kick")
+            .description("kick a user")
+            .default_member_permissions(Permissions::KICK_MEMBERS)
+            .dm_permission(false)
+            .function(noop)
+            .build();
+        let handler = CommandHandler::new(vec![ban, kick], Vec::new());
+        let errors = messages(handler.validate(&CommandScope::Global, None));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("'mod': conflicting permissions: "));
+
+        let warn = Command::builder()
+            .name("mod warn")
+            .description("warn a user")
+            .dm_permission(false)
+            .function(noop)
+            .build();
+        let handler = CommandHandler::new(vec![command("mod kick"), warn], Vec::new());
+        assert!(handler.validate(&CommandScope::Global, None).is_empty());
+        assert_eq!(handler.permissions("mod kick").dm_permission, Some(false));
+    }
+
+    #[test]
+    fn validates_localized_schema() {
+        let parameter = Parameter::builder()
+            .name("user")
+            .description("a user")
+            .kind(ParameterType::User)
+            .build()
+            .unwrap();
+        let handler = CommandHandler::new(
+            vec![Command::builder()
+                .name("greet")
+                .description("greet a user")
+                .parameter(parameter)
+                .function(noop)
+                .build()],
+            Vec::new(),
+        );
+        let mut localizations = Localizations::new("en-US");
+        localizations
+            .add_toml(
+                "de",
+                r#"
+                [commands.greet]
+                name = "Grüßen"
+                description = "einen Benutzer grüßen"
+
+                [commands.greet.parameters.user]
+                name = "benutzer"
+                description = ""
+                "#,
+            )
+            .unwrap();
+        assert_eq!(
+            messages(handler.validate(&CommandScope::Global, Some(&localizations))),
+            vec![
+                "'greet [de]': name 'Grüßen' must be lowercase",
+                "'greet user [de]': description must be 1-100 characters",
+            ]
+        );
+        assert!(handler.validate(&CommandScope::Global, None).is_empty());
+    }
+
+    #[test]
+    fn uses_group_localizations() {
+        let mut admin = group("admin", CommandPermissions::default());
+        admin.name_localizations = Some(HashMap::from([(
+            String::from("de"),
+            String::from("verwaltung"),
+        )]));
+        let mut role = group("admin role", CommandPermissions::default());
+        role.description_localizations = Some(HashMap::from([(
+            String::from("fr"),
+            String::from("gérer les rôles"),
+        )]));
+        let handler = CommandHandler::new(vec![command("admin role add")], vec![admin, role]);
+        let mut localizations = Localizations::new("en-US");
+        localizations.insert("fr", "commands.admin.name", "gestion");
+        localizations.insert("de", "commands.admin.name", "admin");
+
+        let mut payloads = handler.create_application_commands(&CommandScope::Global);
+        let command = &mut payloads.get_mut(&RegistrationTarget::Global).unwrap()[0];
+        assert_eq!(
+            command.name_localizations,
+            Some(HashMap::from([(
+                String::from("de"),
+                String::from("verwaltung")
+            )]))
+        );
+        assert_eq!(
+            command.options[0].description_localizations,
+            Some(HashMap::from([(
+                String::from("fr"),
+                String::from("gérer les rôles")
+            )]))
+        );
+
+        localizations.apply(command);
+        assert_eq!(
+            command.name_localizations,
+            Some(HashMap::from([
+                (String::from("de"), String::from("admin")),
+                (String::from("fr"), String::from("gestion")),
+            ]))
+        );
+        assert_eq!(
+            command.options[0].description_localizations,
+            Some(HashMap::from([(
+                String::from("fr"),
+                String::from("gérer les rôles")
+            )]))
+        );
+    }
+
+    #[test]
+    fn checks_and_localizes_defaults() {
+        let parameter = |default: &str| {
+            Parameter::builder()
+                .name("size")
+                .description("the size")
+                .kind(ParameterType::Integer)
+                .choices(vec![
+                    ParameterChoice::new("Small", ParameterChoiceType::Integer(1)),
+                    ParameterChoice::new("Large", ParameterChoiceType::Integer(2)),
+                ])
+                .default_value(Some(String::from(default)))
+                .build()
+                .unwrap()
+        };
+        let command = |name: &str, default: &str| {
+            Command::builder()
+                .name(name)
+                .description("a command")
+                .parameter(parameter(default))
+                .function(noop)
+                .build()
+        };
+        let handler = CommandHandler::new(
+            vec![command("shirt", "2"), command("shop hat", "3")],
+            Vec::new(),
+        );
+        assert_eq!(
+            messages(handler.validate(&CommandScope::Global, None)),
+            vec!["'shop hat size': default must be one of the choices"]
+        );
+
+        let mut localizations = Localizations::new("en-US");
+        localizations.insert(
+            "de",
+            "commands.shirt.parameters.size.description",
+            "die Größe",
+        );
+        localizations.insert("de", "commands.shirt.parameters.size.choices.Large", "Groß");
+        localizations.insert("de", "parameters.default", " (Standard: {value})");
+        localizations.insert(
+            "fr",
+            "commands.shirt.parameters.size.description",
+            "la taille",
+        );
+        let mut payloads = handler.create_application_commands(&CommandScope::Global);
+        let shirt = payloads
+            .get_mut(&RegistrationTarget::Global)
+            .unwrap()
+            .iter_mut()
+            .find(|command| command.name == "shirt")
+            .unwrap();
+        handler.localize(shirt, &localizations);
+        assert_eq!(shirt.options[0].description, "the size (default: Large)");
+        assert_eq!(
+            shirt.options[0].description_localizations,
+            Some(HashMap::from([
+                (
+                    String::from("de"),
+                    String::from("die Größe (Standard: Groß)")
+                ),
+                (
+                    String::from("fr"),
+                    String::from("la taille (default: Large)")
+                ),
+            ]))
+        );
+    }
+
+    #[test]
+    fn reports_parameter_errors() {
+        let command = Command::builder()
+            .name("echo")
+            .description("echo text")
+            .try_parameter(
+                Parameter::builder()
+                    .name("text")
+                    .description("the text")
+                    .kind(ParameterType::String)
+                    .min_length(Some(-1))
+                    .max_length(Some(7000))
+                    .build(),
+            )
+            .try_parameter(
+                Parameter::builder()
+                    .name("count")
+                    .description("a count")
+                    .build(),
+            )
+            .function(noop)
+            .build();
+        let handler = CommandHandler::new(vec![command], Vec::new());
+        assert_eq!(
+            messages(handler.validate(&CommandScope::Global, None)),
+            vec![
+                "'echo count': parameter kind must be set",
+                "'echo text': length limits must be 0-6000",
+            ]
+        );
+    }
+
+    #[test]
+    fn adjacency_handles_non_ascii_names() {
+        let names = [String::from("café größe ñame"), String::from("café x")];
+        let adjacency = build_adjacency(names.iter());
+        assert_eq!(
+            adjacency["café"],
+            HashSet::from([String::from("café größe"), String::from("café x")])
+        );
+        assert_eq!(
+            adjacency["café größe"],
+            HashSet::from([String::from("café größe ñame")])
+        );
+        assert!(adjacency["café größe ñame"].is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{BoxedFuture, CommandResult, ComponentContext};
+use std::fmt::{self, Display};
+
+pub type ComponentFunction = fn(ComponentContext, Vec<String>) -> BoxedFuture<CommandResult>;
+
+const SEPARATOR: char = ':';
+const ESCAPE: char = '\\';
+const MAX_LENGTH: usize = 100;
+
+pub struct Component {
+    pub prefix: String,
+    pub function: ComponentFunction,
+}
+
+#[derive(Clone, Debug)]
+pub struct CustomId {
+    id: String,
+}
+impl CustomId {
+    pub fn new(prefix: &str) -> Self {
+        let mut id = String::new();
+        escape_into(&mut id, prefix);
+        Self { id }
+    }
+    pub fn arg(mut self, arg: impl Display) -> Self {
+        self.id.push(SEPARATOR);
+        escape_into(&mut self.id, &arg.to_string());
+        self
+    }
+    pub fn build(self) -> Result<String, CustomIdError> {
+        let length = self.id.chars().count();
+        if length > MAX_LENGTH {
+            return Err(CustomIdError { length });
+        }
+        Ok(self.id)
+    }
+    pub fn parse(custom_id: &str) -> (String, Vec<String>) {
+        let mut parts = Vec::new();
+        let mut current = String::new();
+        let mut chars = custom_id.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                ESCAPE => {
+                    if let Some(escaped) = chars.next() {
+                        current.push(escaped);
+                    }
+                }
+                SEPARATOR => parts.push(std::mem::take(&mut current)),
+                _ => current.push(c),
+            }
+        }
+        parts.push(current);
+
+        let prefix = parts.remove(0);
+        (prefix, parts)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CustomIdError {
+    length: usize,
+}
+impl CustomIdError {
+    pub fn length(&self) -> usize {
+        self.length
+    }
+}
+impl fmt::Display for CustomIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "custom id is {} characters long, the limit is {MAX_LENGTH}",
+            self.length
+        )
+    }
+}
+impl std::error::Error for CustomIdError {}
+
+fn escape_into(id: &mut String, s: &str) {
+    for c in s.chars() {
+        if c == SEPARATOR || c == ESCAPE {
+            id.push(ESCAPE);
+        }
+        id.push(c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let id = CustomId::new("pre:fix")
+            .arg("a:b")
+            .arg("c\\d")
+            .arg("")
+            .arg("\\:")
+            .build()
+            .unwrap();
+        let (prefix, args) = CustomId::parse(&id);
+        assert_eq!(prefix, "pre:fix");
+        assert_eq!(args, ["a:b", "c\\d", "", "\\:"]);
+    }
+
+    #[test]
+    fn no_arguments() {
+        let id = CustomId::new("counter").build().unwrap();
+        assert_eq!(id, "counter");
+        assert_eq!(CustomId::parse(&id), (String::from("counter"), Vec::new()));
+    }
+
+    #[test]
+    fn too_long() {
+        assert!(CustomId::new("x").arg("y".repeat(98)).build().is_ok());
+        let error = CustomId::new("x").arg("y".repeat(99)).build().unwrap_err();
+        assert_eq!(error.length(), 101);
+        // escapes count towards the limit
+        let error = CustomId::new("x").arg(":".repeat(50)).build().unwrap_err();
+        assert_eq!(error.length(), 102);
+    }
+}
@@ -1,43 +1,606 @@
-use crate::{Client, State};
+use crate::modal::modal_fields;
+use crate::{Client, Error, Localizations, Modal, State};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use tokio::sync::Mutex;
+use twilight_http::request::channel::message::CreateMessage;
 use twilight_model::{
-    application::interaction::Interaction,
-    http::interaction::{
-        InteractionResponse,
-        InteractionResponseType,
-        InteractionResponseData
+    application::interaction::{Interaction, InteractionData},
+    channel::message::{Message, MessageFlags},
+    http::interaction::{InteractionResponse, InteractionResponseData, InteractionResponseType},
+    id::{
+        marker::{ChannelMarker, GuildMarker, MessageMarker, UserMarker},
+        Id,
     },
+    user::User,
 };
-use twilight_util::builder::InteractionResponseDataBuilder;
-use twilight_http::response::Response;
+use twilight_validate::message::MessageValidationError;
 
 pub struct EventContext {
     pub state: Arc<State>,
 }
+pub struct AutocompleteContext {
+    pub state: Arc<State>,
+    pub client: Arc<Client>,
+    pub(crate) interaction: Interaction,
+}
+impl AutocompleteContext {
+    pub fn interaction(&self) -> &Interaction {
+        &self.interaction
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ResponseState {
+    Pending,
+    Deferred,
+    Responded,
+}
+
+struct Response {
+    state: ResponseState,
+    reply_id: Option<Id<MessageMarker>>,
+}
+
+#[derive(Clone, Debug)]
+pub enum CommandSource {
+    Interaction(Box<Interaction>),
+    Message(Box<Message>),
+}
+
+#[derive(Clone)]
 pub struct CommandContext {
     pub state: Arc<State>,
     pub client: Arc<Client>,
-    pub interaction: Interaction,
+    source: CommandSource,
+    localizations: Option<Arc<Localizations>>,
+    response: Arc<Mutex<Response>>,
 }
 impl CommandContext {
-    pub async fn respond(&self, data: InteractionResponseData) {
-        let response = InteractionResponse {
-            kind: InteractionResponseType::ChannelMessageWithSource,
-            data: Some(data)
+    pub fn new(
+        state: Arc<State>,
+        client: Arc<Client>,
+        interaction: Interaction,
+        localizations: Option<Arc<Localizations>>,
+    ) -> Self {
+        Self::with_source(
+            state,
+            client,
+            CommandSource::Interaction(Box::new(interaction)),
+            localizations,
+        )
+    }
+    pub fn from_message(
+        state: Arc<State>,
+        client: Arc<Client>,
+        message: Message,
+        localizations: Option<Arc<Localizations>>,
+    ) -> Self {
+        Self::with_source(
+            state,
+            client,
+            CommandSource::Message(Box::new(message)),
+            localizations,
+        )
+    }
+    fn with_source(
+        state: Arc<State>,
+        client: Arc<Client>,
+        source: CommandSource,
+        localizations: Option<Arc<Localizations>>,
+    ) -> Self {
+        Self {
+            state,
+            client,
+            source,
+            localizations,
+            response: Arc::new(Mutex::new(Response {
+                state: ResponseState::Pending,
+                reply_id: None,
+            })),
+        }
+    }
+    pub fn source(&self) -> &CommandSource {
+        &self.source
+    }
+    pub fn interaction(&self) -> Option<&Interaction> {
+        match &self.source {
+            CommandSource::Interaction(interaction) => Some(interaction),
+            CommandSource::Message(_) => None,
+        }
+    }
+    pub fn message(&self) -> Option<&Message> {
+        match &self.source {
+            CommandSource::Interaction(_) => None,
+            CommandSource::Message(message) => Some(message),
+        }
+    }
+    pub fn author(&self) -> Option<&User> {
+        match &self.source {
+            CommandSource::Interaction(interaction) => interaction.author(),
+            CommandSource::Message(message) => Some(&message.author),
+        }
+    }
+    pub fn author_id(&self) -> Option<Id<UserMarker>> {
+        self.author().map(|user| user.id)
+    }
+    pub fn guild_id(&self) -> Option<Id<GuildMarker>> {
+        match &self.source {
+            CommandSource::Interaction(interaction) => interaction.guild_id,
+            CommandSource::Message(message) => message.guild_id,
+        }
+    }
+    pub fn channel_id(&self) -> Option<Id<ChannelMarker>> {
+        match &self.source {
+            CommandSource::Interaction(interaction) => {
+                interaction.channel.as_ref().map(|channel| channel.id)
+            }
+            CommandSource::Message(message) => Some(message.channel_id),
+        }
+    }
+    pub fn t(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let Some(localizations) = &self.localizations else {
+            return String::from(key);
+        };
+        let locales: Vec<String> = match &self.source {
+            CommandSource::Interaction(interaction) => {
+                [&interaction.locale, &interaction.guild_locale]
+                    .into_iter()
+                    .flatten()
+                    .cloned()
+                    .collect()
+            }
+            CommandSource::Message(message) => message
+                .guild_id
+                .and_then(|id| self.client.cache.guild(id))
+                .map(|guild| String::from(guild.preferred_locale()))
+                .into_iter()
+                .collect(),
+        };
+        let locales: Vec<&str> = locales.iter().map(|x| x.as_str()).collect();
+        localizations.translate(&locales, key, args)
+    }
+    pub async fn is_acknowledged(&self) -> bool {
+        self.response.lock().await.state != ResponseState::Pending
+    }
+    pub async fn respond(&self, data: InteractionResponseData) -> Result<(), Error> {
+        let mut response = self.response.lock().await;
+        match (&self.source, response.state) {
+            (CommandSource::Interaction(interaction), ResponseState::Pending) => {
+                create_response(
+                    &self.client,
+                    interaction,
+                    InteractionResponseType::ChannelMessageWithSource,
+                    Some(data),
+                )
+                .await?;
+            }
+            (CommandSource::Interaction(_), ResponseState::Deferred) => {
+                self.edit_response(data).await?;
+            }
+            (CommandSource::Message(message), ResponseState::Pending | ResponseState::Deferred) => {
+                let reply = create_message(&self.client, message.channel_id, &data)?
+                    .reply(message.id)
+                    .await?
+                    .model()
+                    .await?;
+                response.reply_id = Some(reply.id);
+            }
+            (_, ResponseState::Responded) => {
+                self.followup(data).await?;
+            }
+        }
+        response.state = ResponseState::Responded;
+        Ok(())
+    }
+    pub async fn defer(&self, ephemeral: bool) -> Result<(), Error> {
+        let mut response = self.response.lock().await;
+        if response.state != ResponseState::Pending {
+            tracing::warn!("command was already acknowledged");
+            return Ok(());
+        }
+        match &self.source {
+            CommandSource::Interaction(interaction) => {
+                let data = InteractionResponseData {
+                    flags: ephemeral.then_some(MessageFlags::EPHEMERAL),
+                    ..Default::default()
+                };
+                create_response(
+                    &self.client,
+                    interaction,
+                    InteractionResponseType::DeferredChannelMessageWithSource,
+                    Some(data),
+                )
+                .await?;
+            }
+            CommandSource::Message(message) => {
+                self.client
+                    .http
+                    .create_typing_trigger(message.channel_id)
+                    .await?;
+            }
+        }
+        response.state = ResponseState::Deferred;
+        Ok(())
+    }
+    pub async fn respond_modal<M: Modal>(&self, custom_id: impl Into<String>) -> Result<(), Error> {
+        let CommandSource::Interaction(interaction) = &self.source else {
+            return Err(Error::user("This command only works as a slash command."));
         };
-        let result = self.client.http
-            .interaction(self.interaction.application_id.clone())
-            .create_response(self.interaction.id.clone(), &self.interaction.token, &response)
-            .await;
+        let mut response = self.response.lock().await;
+        if response.state != ResponseState::Pending {
+            return Err(Error::Other(
+                "cannot respond with a modal, the interaction was already acknowledged".into(),
+            ));
+        }
+        create_response(
+            &self.client,
+            interaction,
+            InteractionResponseType::Modal,
+            Some(M::create_response_data(custom_id)),
+        )
+        .await?;
+        response.state = ResponseState::Responded;
+        Ok(())
+    }
+    pub async fn edit_response(&self, data: InteractionResponseData) -> Result<Message, Error> {
+        let message = match &self.source {
+            CommandSource::Interaction(interaction) => {
+                self.client
+                    .http
+                    .interaction(interaction.application_id)
+                    .update_response(&interaction.token)
+                    .content(data.content.as_deref())?
+                    .embeds(data.embeds.as_deref())?
+                    .components(data.components.as_deref())?
+                    .attachments(data.attachments.as_deref().unwrap_or_default())?
+                    .allowed_mentions(data.allowed_mentions.as_ref())
+                    .await?
+                    .model()
+                    .await?
+            }
+            CommandSource::Message(message) => {
+                let mut response = self.response.lock().await;
+                match response.reply_id {
+                    Some(reply_id) => {
+                        update_message(&self.client, message.channel_id, reply_id, &data).await?
+                    }
+                    None => {
+                        let reply = create_message(&self.client, message.channel_id, &data)?
+                            .reply(message.id)
+                            .await?
+                            .model()
+                            .await?;
+                        response.reply_id = Some(reply.id);
+                        response.state = ResponseState::Responded;
+                        reply
+                    }
+                }
+            }
+        };
+        Ok(message)
+    }
+    pub async fn followup(&self, data: InteractionResponseData) -> Result<Message, Error> {
+        let interaction = match &self.source {
+            CommandSource::Interaction(interaction) => interaction,
+            CommandSource::Message(message) => {
+                let reply = create_message(&self.client, message.channel_id, &data)?
+                    .await?
+                    .model()
+                    .await?;
+                return Ok(reply);
+            }
+        };
+        let interaction_client = self.client.http.interaction(interaction.application_id);
+        let mut request = interaction_client
+            .create_followup(&interaction.token)
+            .embeds(data.embeds.as_deref().unwrap_or_default())?
+            .components(data.components.as_deref().unwrap_or_default())?
+            .attachments(data.attachments.as_deref().unwrap_or_default())?
+            .allowed_mentions(data.allowed_mentions.as_ref())
+            .tts(data.tts.unwrap_or(false));
+        if let Some(content) = &data.content {
+            request = request.content(content)?;
+        }
+        if let Some(flags) = data.flags {
+            request = request.flags(flags);
+        }
+        Ok(request.await?.model().await?)
+    }
+    pub async fn edit_followup(
+        &self,
+        message_id: Id<MessageMarker>,
+        data: InteractionResponseData,
+    ) -> Result<Message, Error> {
+        let message = match &self.source {
+            CommandSource::Interaction(interaction) => {
+                self.client
+                    .http
+                    .interaction(interaction.application_id)
+                    .update_followup(&interaction.token, message_id)
+                    .content(data.content.as_deref())?
+                    .embeds(data.embeds.as_deref())?
+                    .components(data.components.as_deref())?
+                    .attachments(data.attachments.as_deref().unwrap_or_default())?
+                    .allowed_mentions(data.allowed_mentions.as_ref())
+                    .await?
+                    .model()
+                    .await?
+            }
+            CommandSource::Message(message) => {
+                update_message(&self.client, message.channel_id, message_id, &data).await?
+            }
+        };
+        Ok(message)
+    }
+    pub async fn delete_followup(&self, message_id: Id<MessageMarker>) -> Result<(), Error> {
+        match &self.source {
+            CommandSource::Interaction(interaction) => {
+                self.client
+                    .http
+                    .interaction(interaction.application_id)
+                    .delete_followup(&interaction.token, message_id)
+                    .await?;
+            }
+            CommandSource::Message(message) => {
+                self.client
+                    .http
+                    .delete_message(message.channel_id, message_id)
+                    .await?;
+            }
+        }
+        Ok(())
     }
-    pub async fn delete_response(&self) {
-        let result = self.client.http
-            .interaction(self.interaction.application_id.clone())
-            .delete_response(&self.interaction.token)
-            .await;
-        if !result.as_ref().is_ok_and(|x| x.status().is_success()) {
-            tracing::error!("failed to delete response: {result:?}");
+    pub async fn delete_response(&self) -> Result<(), Error> {
+        match &self.source {
+            CommandSource::Interaction(interaction) => {
+                self.client
+                    .http
+                    .interaction(interaction.application_id)
+                    .delete_response(&interaction.token)
+                    .await?;
+            }
+            CommandSource::Message(message) => {
+                let reply_id = self.response.lock().await.reply_id.take();
+                if let Some(reply_id) = reply_id {
+                    self.client
+                        .http
+                        .delete_message(message.channel_id, reply_id)
+                        .await?;
+                }
+            }
         }
+        Ok(())
     }
+}
+
+#[derive(Clone)]
+pub struct ComponentContext {
+    pub state: Arc<State>,
+    pub client: Arc<Client>,
+    pub(crate) interaction: Interaction,
+    acknowledged: Arc<AtomicBool>,
+}
+impl ComponentContext {
+    pub(crate) fn new(state: Arc<State>, client: Arc<Client>, interaction: Interaction) -> Self {
+        Self {
+            state,
+            client,
+            interaction,
+            acknowledged: Arc::new(AtomicBool::new(false)),
+        }
+    }
+    pub fn interaction(&self) -> &Interaction {
+        &self.interaction
+    }
+    pub fn is_acknowledged(&self) -> bool {
+        self.acknowledged.load(Ordering::Acquire)
+    }
+    async fn create_response(
+        &self,
+        kind: InteractionResponseType,
+        data: Option<InteractionResponseData>,
+    ) -> Result<(), Error> {
+        create_response(&self.client, &self.interaction, kind, data).await?;
+        self.acknowledged.store(true, Ordering::Release);
+        Ok(())
+    }
+    pub fn custom_id(&self) -> &str {
+        match &self.interaction.data {
+            Some(InteractionData::MessageComponent(data)) => data.custom_id.as_str(),
+            _ => "",
+        }
+    }
+    pub fn values(&self) -> &[String] {
+        match &self.interaction.data {
+            Some(InteractionData::MessageComponent(data)) => data.values.as_slice(),
+            _ => &[],
+        }
+    }
+    pub async fn respond(&self, data: InteractionResponseData) -> Result<(), Error> {
+        self.create_response(
+            InteractionResponseType::ChannelMessageWithSource,
+            Some(data),
+        )
+        .await
+    }
+    pub async fn update_message(&self, data: InteractionResponseData) -> Result<(), Error> {
+        self.create_response(InteractionResponseType::UpdateMessage, Some(data))
+            .await
+    }
+    pub async fn respond_modal<M: Modal>(&self, custom_id: impl Into<String>) -> Result<(), Error> {
+        self.create_response(
+            InteractionResponseType::Modal,
+            Some(M::create_response_data(custom_id)),
+        )
+        .await
+    }
+}
+
+#[derive(Clone)]
+pub struct ModalContext {
+    pub state: Arc<State>,
+    pub client: Arc<Client>,
+    pub(crate) interaction: Interaction,
+    acknowledged: Arc<AtomicBool>,
+}
+impl ModalContext {
+    pub(crate) fn new(state: Arc<State>, client: Arc<Client>, interaction: Interaction) -> Self {
+        Self {
+            state,
+            client,
+            interaction,
+            acknowledged: Arc::new(AtomicBool::new(false)),
+        }
+    }
+    pub fn interaction(&self) -> &Interaction {
+        &self.interaction
+    }
+    pub fn is_acknowledged(&self) -> bool {
+        self.acknowledged.load(Ordering::Acquire)
+    }
+    async fn create_response(
+        &self,
+        kind: InteractionResponseType,
+        data: Option<InteractionResponseData>,
+    ) -> Result<(), Error> {
+        create_response(&self.client, &self.interaction, kind, data).await?;
+        self.acknowledged.store(true, Ordering::Release);
+        Ok(())
+    }
+    pub fn custom_id(&self) -> &str {
+        match &self.interaction.data {
+            Some(InteractionData::ModalSubmit(data)) => data.custom_id.as_str(),
+            _ => "",
+        }
+    }
+    pub fn fields(&self) -> HashMap<String, String> {
+        match &self.interaction.data {
+            Some(InteractionData::ModalSubmit(data)) => modal_fields(data),
+            _ => HashMap::new(),
+        }
+    }
+    pub async fn respond(&self, data: InteractionResponseData) -> Result<(), Error> {
+        self.create_response(
+            InteractionResponseType::ChannelMessageWithSource,
+            Some(data),
+        )
+        .await
+    }
+    pub async fn update_message(&self, data: InteractionResponseData) -> Result<(), Error> {
+        self.create_response(InteractionResponseType::UpdateMessage, Some(data))
+            .await
+    }
+}
 
+fn create_message<'a>(
+    client: &'a Client,
+    channel_id: Id<ChannelMarker>,
+    data: &'a InteractionResponseData,
+) -> Result<CreateMessage<'a>, MessageValidationError> {
+    let mut request = client
+        .http
+        .create_message(channel_id)
+        .embeds(data.embeds.as_deref().unwrap_or_default())?
+        .components(data.components.as_deref().unwrap_or_default())?
+        .attachments(data.attachments.as_deref().unwrap_or_default())?
+        .allowed_mentions(data.allowed_mentions.as_ref())
+        .tts(data.tts.unwrap_or(false));
+    if let Some(content) = &data.content {
+        request = request.content(content)?;
+    }
+    if let Some(flags) = data.flags {
+        request = request.flags(flags - MessageFlags::EPHEMERAL);
+    }
+    Ok(request)
+}
+
+async fn update_message(
+    client: &Client,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+    data: &InteractionResponseData,
+) -> Result<Message, Error> {
+    let message = client
+        .http
+        .update_message(channel_id, message_id)
+        .content(data.content.as_deref())?
+        .embeds(data.embeds.as_deref())?
+        .components(data.components.as_deref())?
+        .attachments(data.attachments.as_deref().unwrap_or_default())?
+        .allowed_mentions(data.allowed_mentions.as_ref())
+        .await?
+        .model()
+        .await?;
+    Ok(message)
+}
+
+async fn create_response(
+    client: &Client,
+    interaction: &Interaction,
+    kind: InteractionResponseType,
+    data: Option<InteractionResponseData>,
+) -> Result<(), Error> {
+    let response = InteractionResponse { kind, data };
+    client
+        .http
+        .interaction(interaction.application_id)
+        .create_response(interaction.id, &interaction.token, &response)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::RwLock;
+    use twilight_cache_inmemory::InMemoryCache;
+    use typemap_rev::TypeMap;
+
+    fn context(locale: Option<&str>, guild_locale: Option<&str>) -> CommandContext {
+        let interaction = serde_json::from_value(serde_json::json!({
+            "application_id": "1",
+            "data": {"id": "3", "name": "cmd", "type": 1},
+            "guild_locale": guild_locale,
+            "id": "2",
+            "locale": locale,
+            "token": "token",
+            "type": 2,
+            "version": 1,
+        }))
+        .unwrap();
+        let mut localizations = Localizations::new("en-US");
+        localizations.insert("en-US", "count", "Count: {count}");
+        localizations.insert("de", "count", "Anzahl: {count}");
+        localizations.insert("fr", "count", "Nombre : {count}");
+        CommandContext::new(
+            Arc::new(State {
+                storage: RwLock::new(TypeMap::new()),
+            }),
+            Arc::new(Client {
+                http: twilight_http::Client::new(String::new()),
+                cache: InMemoryCache::new(),
+                application_id: Id::new(1),
+            }),
+            interaction,
+            Some(Arc::new(localizations)),
+        )
+    }
+
+    #[test]
+    fn translates_with_locale_then_guild_locale_then_default() {
+        let args: &[(&str, &dyn Display)] = &[("count", &5)];
+        assert_eq!(
+            context(Some("de"), Some("fr")).t("count", args),
+            "Anzahl: 5"
+        );
+        assert_eq!(
+            context(Some("ja"), Some("fr")).t("count", args),
+            "Nombre : 5"
+        );
+        assert_eq!(context(None, Some("fr")).t("count", args), "Nombre : 5");
+        assert_eq!(context(Some("ja"), None).t("count", args), "Count: 5");
+        assert_eq!(context(None, None).t("missing", args), "missing");
+    }
 }
@@ -0,0 +1,309 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use twilight_model::id::{
+    marker::{ChannelMarker, GuildMarker, UserMarker},
+    Id,
+};
+
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CooldownBucket {
+    User,
+    Member,
+    Channel,
+    Guild,
+    Global,
+}
+impl CooldownBucket {
+    pub fn key(
+        &self,
+        user_id: Option<Id<UserMarker>>,
+        channel_id: Option<Id<ChannelMarker>>,
+        guild_id: Option<Id<GuildMarker>>,
+    ) -> Option<CooldownKey> {
+        let key = match self {
+            Self::User => CooldownKey::User(user_id?),
+            Self::Member => match guild_id {
+                Some(guild_id) => CooldownKey::Member(guild_id, user_id?),
+                None => CooldownKey::User(user_id?),
+            },
+            Self::Channel => CooldownKey::Channel(channel_id?),
+            Self::Guild => match guild_id {
+                Some(guild_id) => CooldownKey::Guild(guild_id),
+                None => CooldownKey::Channel(channel_id?),
+            },
+            Self::Global => CooldownKey::Global,
+        };
+        Some(key)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CooldownKey {
+    User(Id<UserMarker>),
+    Member(Id<GuildMarker>, Id<UserMarker>),
+    Channel(Id<ChannelMarker>),
+    Guild(Id<GuildMarker>),
+    Global,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Cooldown {
+    pub bucket: CooldownBucket,
+    pub rate: u32,
+    pub per: Duration,
+}
+impl Cooldown {
+    pub fn new(bucket: CooldownBucket, rate: u32, per: Duration) -> Self {
+        assert!(rate > 0);
+        Self { bucket, rate, per }
+    }
+}
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+struct CooldownEntry {
+    per: Duration,
+    uses: VecDeque<Instant>,
+}
+impl CooldownEntry {
+    fn expire(&mut self, now: Instant) {
+        while let Some(&oldest) = self.uses.front() {
+            if now.duration_since(oldest) < self.per {
+                break;
+            }
+            self.uses.pop_front();
+        }
+    }
+}
+
+struct CooldownEntries {
+    last_sweep: Instant,
+    entries: HashMap<(String, CooldownKey), CooldownEntry>,
+}
+
+pub struct CooldownTracker {
+    clock: Arc<dyn Clock>,
+    entries: Mutex<CooldownEntries>,
+}
+impl CooldownTracker {
+    pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        let entries = CooldownEntries {
+            last_sweep: clock.now(),
+            entries: HashMap::new(),
+        };
+        Self {
+            clock,
+            entries: Mutex::new(entries),
+        }
+    }
+    pub fn hit(
+        &self,
+        command: &str,
+        cooldown: &Cooldown,
+        key: CooldownKey,
+    ) -> Result<(), Duration> {
+        let now = self.clock.now();
+        let mut entries = self.entries.lock().unwrap();
+
+        if now.duration_since(entries.last_sweep) >= SWEEP_INTERVAL {
+            entries.entries.retain(|_, entry| {
+                entry.expire(now);
+                !entry.uses.is_empty()
+            });
+            entries.last_sweep = now;
+        }
+
+        let entry = entries
+            .entries
+            .entry((String::from(command), key))
+            .or_insert_with(|| CooldownEntry {
+                per: cooldown.per,
+                uses: VecDeque::new(),
+            });
+        entry.per = cooldown.per;
+        entry.expire(now);
+
+        if entry.uses.len() >= cooldown.rate as usize {
+            let oldest = entry.uses[entry.uses.len() - cooldown.rate as usize];
+            return Err(cooldown.per - now.duration_since(oldest));
+        }
+        entry.uses.push_back(now);
+        Ok(())
+    }
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+impl Default for CooldownTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeClock {
+        start: Instant,
+        elapsed: Mutex<Duration>,
+    }
+    impl FakeClock {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                start: Instant::now(),
+                elapsed: Mutex::new(Duration::ZERO),
+            })
+        }
+        fn advance(&self, duration: Duration) {
+            *self.elapsed.lock().unwrap() += duration;
+        }
+    }
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            self.start + *self.elapsed.lock().unwrap()
+        }
+    }
+
+    fn cooldown(bucket: CooldownBucket) -> Cooldown {
+        Cooldown::new(bucket, 2, Duration::from_secs(10))
+    }
+
+    #[test]
+    fn rate_is_exhausted() {
+        let tracker = CooldownTracker::with_clock(FakeClock::new());
+        let cooldown = cooldown(CooldownBucket::Global);
+        assert_eq!(tracker.hit("cmd", &cooldown, CooldownKey::Global), Ok(()));
+        assert_eq!(tracker.hit("cmd", &cooldown, CooldownKey::Global), Ok(()));
+        assert_eq!(
+            tracker.hit("cmd", &cooldown, CooldownKey::Global),
+            Err(Duration::from_secs(10))
+        );
+    }
+
+    #[test]
+    fn window_expires_and_resets() {
+        let clock = FakeClock::new();
+        let tracker = CooldownTracker::with_clock(clock.clone());
+        let cooldown = cooldown(CooldownBucket::Global);
+        tracker.hit("cmd", &cooldown, CooldownKey::Global).unwrap();
+        clock.advance(Duration::from_secs(4));
+        tracker.hit("cmd", &cooldown, CooldownKey::Global).unwrap();
+
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(
+            tracker.hit("cmd", &cooldown, CooldownKey::Global),
+            Err(Duration::from_secs(3))
+        );
+
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(tracker.hit("cmd", &cooldown, CooldownKey::Global), Ok(()));
+        assert!(tracker.hit("cmd", &cooldown, CooldownKey::Global).is_err());
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(tracker.hit("cmd", &cooldown, CooldownKey::Global), Ok(()));
+        assert_eq!(tracker.hit("cmd", &cooldown, CooldownKey::Global), Ok(()));
+    }
+
+    #[test]
+    fn idle_entries_are_swept() {
+        let clock = FakeClock::new();
+        let tracker = CooldownTracker::with_clock(clock.clone());
+        let cooldown = cooldown(CooldownBucket::User);
+        tracker
+            .hit("cmd", &cooldown, CooldownKey::User(Id::new(1)))
+            .unwrap();
+        assert_eq!(tracker.len(), 1);
+        clock.advance(SWEEP_INTERVAL);
+        tracker
+            .hit("cmd", &cooldown, CooldownKey::User(Id::new(2)))
+            .unwrap();
+        assert_eq!(tracker.len(), 1);
+    }
+
+    #[test]
+    fn user_buckets_are_isolated() {
+        let tracker = CooldownTracker::with_clock(FakeClock::new());
+        let cooldown = cooldown(CooldownBucket::User);
+        let key = |user| {
+            cooldown
+                .bucket
+                .key(Some(Id::new(user)), Some(Id::new(5)), None)
+        };
+        for _ in 0..2 {
+            tracker.hit("cmd", &cooldown, key(1).unwrap()).unwrap();
+        }
+        assert!(tracker.hit("cmd", &cooldown, key(1).unwrap()).is_err());
+        assert_eq!(tracker.hit("cmd", &cooldown, key(2).unwrap()), Ok(()));
+        assert_eq!(tracker.hit("other", &cooldown, key(1).unwrap()), Ok(()));
+    }
+
+    #[test]
+    fn guild_buckets_are_isolated() {
+        let tracker = CooldownTracker::with_clock(FakeClock::new());
+        let cooldown = cooldown(CooldownBucket::Guild);
+        let key = |user, guild| {
+            cooldown
+                .bucket
+                .key(Some(Id::new(user)), Some(Id::new(5)), Some(Id::new(guild)))
+                .unwrap()
+        };
+        tracker.hit("cmd", &cooldown, key(1, 10)).unwrap();
+        tracker.hit("cmd", &cooldown, key(2, 10)).unwrap();
+        assert!(tracker.hit("cmd", &cooldown, key(3, 10)).is_err());
+        assert_eq!(tracker.hit("cmd", &cooldown, key(1, 20)), Ok(()));
+    }
+
+    #[test]
+    fn channel_buckets_are_isolated() {
+        let tracker = CooldownTracker::with_clock(FakeClock::new());
+        let cooldown = cooldown(CooldownBucket::Channel);
+        let key = |user, channel| {
+            cooldown
+                .bucket
+                .key(
+                    Some(Id::new(user)),
+                    Some(Id::new(channel)),
+                    Some(Id::new(10)),
+                )
+                .unwrap()
+        };
+        tracker.hit("cmd", &cooldown, key(1, 5)).unwrap();
+        tracker.hit("cmd", &cooldown, key(2, 5)).unwrap();
+        assert!(tracker.hit("cmd", &cooldown, key(3, 5)).is_err());
+        assert_eq!(tracker.hit("cmd", &cooldown, key(1, 6)), Ok(()));
+    }
+
+    #[test]
+    fn bucket_keys_fall_back_outside_guilds() {
+        let user = Some(Id::new(1));
+        let channel = Some(Id::new(5));
+        assert_eq!(
+            CooldownBucket::Member.key(user, channel, None),
+            Some(CooldownKey::User(Id::new(1)))
+        );
+        assert_eq!(
+            CooldownBucket::Guild.key(user, channel, None),
+            Some(CooldownKey::Channel(Id::new(5)))
+        );
+        assert_eq!(CooldownBucket::User.key(None, channel, None), None);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{BoxedFuture, CommandContext, ComponentContext, CustomIdError, ModalContext};
+use futures_util::FutureExt;
+use std::any::Any;
+use std::fmt;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::time::Duration;
+use twilight_gateway::EventType;
+use twilight_http::response::DeserializeBodyError;
+use twilight_model::channel::message::MessageFlags;
+use twilight_model::http::interaction::InteractionResponseData;
+use twilight_util::builder::InteractionResponseDataBuilder;
+use twilight_validate::message::MessageValidationError;
+
+pub type ErrorHandler = fn(String, CommandContext, Error) -> BoxedFuture<()>;
+pub type ComponentErrorHandler = fn(String, ComponentContext, Error) -> BoxedFuture<()>;
+pub type ModalErrorHandler = fn(String, ModalContext, Error) -> BoxedFuture<()>;
+
+pub fn default_error_handler(name: String, ctx: CommandContext, error: Error) -> BoxedFuture<()> {
+    Box::pin(async move {
+        tracing::error!(?error, "command '{}' failed: {}", name, error);
+
+        if let Err(source) = ctx.respond(error_response(&error, "command")).await {
+            tracing::error!(?source, "failed to report error for command '{}'", name);
+        }
+    })
+}
+
+pub fn default_component_error_handler(
+    prefix: String,
+    ctx: ComponentContext,
+    error: Error,
+) -> BoxedFuture<()> {
+    Box::pin(async move {
+        tracing::error!(?error, "component '{}' failed: {}", prefix, error);
+
+        if ctx.is_acknowledged() {
+            return;
+        }
+        if let Err(source) = ctx.respond(error_response(&error, "component")).await {
+            tracing::error!(?source, "failed to report error for component '{}'", prefix);
+        }
+    })
+}
+
+pub fn default_modal_error_handler(
+    prefix: String,
+    ctx: ModalContext,
+    error: Error,
+) -> BoxedFuture<()> {
+    Box::pin(async move {
+        tracing::error!(?error, "modal '{}' failed: {}", prefix, error);
+
+        if ctx.is_acknowledged() {
+            return;
+        }
+        if let Err(source) = ctx.respond(error_response(&error, "modal")).await {
+            tracing::error!(?source, "failed to report error for modal '{}'", prefix);
+        }
+    })
+}
+
+pub fn unexpected_event(handler: &str, kind: EventType) {
+    tracing::error!("{} received unexpected event {:?}", handler, kind);
+}
+
+fn error_response(error: &Error, source: &str) -> InteractionResponseData {
+    let content = match error {
+        Error::User(message) => message.clone(),
+        Error::Argument { name, reason } => format!("Invalid value for `{name}`: {reason}."),
+        Error::CheckFailed(_) => String::from("You can't use this command here."),
+        Error::Cooldown(remaining) => format!(
+            "This command is on cooldown. Try again in {:.1} seconds.",
+            remaining.as_secs_f64()
+        ),
+        _ => format!("Something went wrong while running this {source}."),
+    };
+    InteractionResponseDataBuilder::new()
+        .content(content)
+        .flags(MessageFlags::EPHEMERAL)
+        .build()
+}
+
+pub async fn catch_panic<T>(future: impl Future<Output = T>) -> Result<T, Error> {
+    AssertUnwindSafe(future)
+        .catch_unwind()
+        .await
+        .map_err(|payload| Error::Panic(panic_message(payload.as_ref())))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        String::from(*message)
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic payload")
+    }
+}
+
+pub fn argument_error(name: &str, reason: &str) -> BoxedFuture<Result<(), Error>> {
+    let error = Error::argument(name, reason);
+    Box::pin(async move { Err(error) })
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Http(twilight_http::Error),
+    Deserialize(DeserializeBodyError),
+    Validation(MessageValidationError),
+    Argument { name: String, reason: String },
+    CheckFailed(String),
+    Cooldown(Duration),
+    User(String),
+    Panic(String),
+    CustomId(CustomIdError),
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+impl Error {
+    pub fn argument(name: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::Argument {
+            name: name.into(),
+            reason: reason.into(),
+        }
+    }
+    pub fn check_failed(reason: impl Into<String>) -> Self {
+        Self::CheckFailed(reason.into())
+    }
+    pub fn user(message: impl Into<String>) -> Self {
+        Self::User(message.into())
+    }
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "http error: {e}"),
+            Self::Deserialize(e) => write!(f, "failed to deserialize response: {e}"),
+            Self::Validation(e) => write!(f, "invalid message: {e}"),
+            Self::Argument { name, reason } => write!(f, "invalid argument '{name}': {reason}"),
+            Self::CheckFailed(reason) => write!(f, "check failed: {reason}"),
+            Self::Cooldown(remaining) => {
+                write!(f, "on cooldown for {:.1}s", remaining.as_secs_f64())
+            }
+            Self::User(message) => write!(f, "{message}"),
+            Self::Panic(message) => write!(f, "panicked: {message}"),
+            Self::CustomId(e) => write!(f, "{e}"),
+            Self::Other(e) => write!(f, "{e}"),
+        }
+    }
+}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Http(e) => Some(e),
+            Self::Deserialize(e) => Some(e),
+            Self::Validation(e) => Some(e),
+            Self::CustomId(e) => Some(e),
+            Self::Other(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+impl From<twilight_http::Error> for Error {
+    fn from(e: twilight_http::Error) -> Self {
+        Self::Http(e)
+    }
+}
+impl From<DeserializeBodyError> for Error {
+    fn from(e: DeserializeBodyError) -> Self {
+        Self::Deserialize(e)
+    }
+}
+impl From<MessageValidationError> for Error {
+    fn from(e: MessageValidationError) -> Self {
+        Self::Validation(e)
+    }
+}
+impl From<CustomIdError> for Error {
+    fn from(e: CustomIdError) -> Self {
+        Self::CustomId(e)
+    }
+}
+impl From<Box<dyn std::error::Error + Send + Sync>> for Error {
+    fn from(e: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        Self::Other(e)
+    }
+}
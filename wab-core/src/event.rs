@@ -7,4 +7,4 @@ pub type EventFunction = fn(Arc<EventData>) -> BoxedFuture<()>;
 pub struct Event {
     pub kind: EventType,
     pub function: EventFunction,
-}
\ No newline at end of file
+}
@@ -1,4 +1,4 @@
-use crate::command::Command;
+use crate::command::{Command, CommandGroup};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use typemap_rev::{TypeMap, TypeMapKey};
@@ -19,11 +19,19 @@ impl SetupContext {
         }
     }
 }
+impl Default for SetupContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 pub type GroupSetupFunction = fn(&mut SetupContext);
 
 pub struct Group {
     pub build_commands: fn() -> Vec<Command>,
+    pub build_command_groups: fn() -> Vec<CommandGroup>,
     pub build_events: fn() -> Vec<crate::Event>,
+    pub build_components: fn() -> Vec<crate::Component>,
+    pub build_modals: fn() -> Vec<crate::ModalHandler>,
     pub setup: Option<GroupSetupFunction>,
 }
@@ -1,21 +1,51 @@
 pub mod argument;
 pub mod bot;
+pub mod check;
 pub mod client;
 pub mod command;
 pub mod command_handler;
+pub mod component;
 pub mod context;
+pub mod cooldown;
+pub mod error;
 pub mod event;
 pub mod group;
+pub mod localization;
+pub mod modal;
 pub mod parameter;
+pub mod prefix;
+pub mod scope;
 pub mod state;
+pub mod sync;
+pub mod validation;
 
-pub use argument::Argument;
+pub use argument::{Argument, FromArgument, FromTarget};
 pub use bot::Bot;
+pub use check::{Check, CheckFunction};
 pub use client::Client;
-pub use command::{BoxedFuture, Command, CommandBuilder, CommandFunction, CommandResult};
+pub use command::{
+    BoxedFuture, Command, CommandBuilder, CommandFunction, CommandGroup, CommandPermissions,
+    CommandResult, TARGET_ARGUMENT,
+};
 pub use command_handler::CommandHandler;
-pub use context::CommandContext;
+pub use component::{Component, ComponentFunction, CustomId, CustomIdError};
+pub use context::{
+    AutocompleteContext, CommandContext, CommandSource, ComponentContext, ModalContext,
+};
+pub use cooldown::{Clock, Cooldown, CooldownBucket, CooldownKey, CooldownTracker};
+pub use error::{ComponentErrorHandler, Error, ErrorHandler, ModalErrorHandler};
 pub use event::{Event, EventFunction};
 pub use group::{Group, SetupContext};
-pub use parameter::{Parameter, ParameterChoice, ParameterChoiceType, ParameterType};
+pub use localization::{LocalizationError, Localizations, MissingTranslation};
+pub use modal::{Modal, ModalFunction, ModalHandler};
+pub use parameter::{
+    AutocompleteFunction, Choices, Parameter, ParameterBuilder, ParameterChoice,
+    ParameterChoiceType, ParameterError, ParameterType,
+};
+pub use prefix::Prefixes;
+pub use scope::{CommandScope, RegistrationTarget};
 pub use state::State;
+pub use sync::{CommandDiff, CommandUpdate};
+pub use validation::ValidationError;
+
+pub use twilight_model;
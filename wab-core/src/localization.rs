@@ -0,0 +1,387 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use twilight_model::application::command::{
+    Command as ApplicationCommand, CommandOption, CommandOptionType,
+};
+
+/// Key of the suffix appended to localized descriptions of parameters with a default.
+pub const DEFAULT_SUFFIX_KEY: &str = "parameters.default";
+pub(crate) const DEFAULT_SUFFIX: &str = " (default: {value})";
+
+#[derive(Debug)]
+pub struct Localizations {
+    default_locale: String,
+    strings: HashMap<String, HashMap<String, String>>,
+    reported_missing: Mutex<HashSet<String>>,
+}
+impl Localizations {
+    pub fn new(default_locale: impl Into<String>) -> Self {
+        Self {
+            default_locale: default_locale.into(),
+            strings: HashMap::new(),
+            reported_missing: Mutex::new(HashSet::new()),
+        }
+    }
+    pub fn load_dir(
+        default_locale: impl Into<String>,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, LocalizationError> {
+        let mut localizations = Self::new(default_locale);
+        let entries = std::fs::read_dir(path.as_ref())
+            .map_err(|e| LocalizationError::Io(path.as_ref().to_path_buf(), e))?;
+
+        for entry in entries {
+            let path = entry
+                .map_err(|e| LocalizationError::Io(path.as_ref().to_path_buf(), e))?
+                .path();
+            if path.extension().and_then(|x| x.to_str()) != Some("toml") {
+                continue;
+            }
+            let Some(locale) = path.file_stem().and_then(|x| x.to_str()) else {
+                continue;
+            };
+            let source = std::fs::read_to_string(&path)
+                .map_err(|e| LocalizationError::Io(path.clone(), e))?;
+            localizations
+                .add_toml(locale, &source)
+                .map_err(|e| match e {
+                    LocalizationError::Parse(_, e) => LocalizationError::Parse(path.clone(), e),
+                    e => e,
+                })?;
+        }
+
+        Ok(localizations)
+    }
+    pub fn add_toml(&mut self, locale: &str, source: &str) -> Result<(), LocalizationError> {
+        let table: toml::Table =
+            toml::from_str(source).map_err(|e| LocalizationError::Parse(PathBuf::new(), e))?;
+        let strings = self.strings.entry(String::from(locale)).or_default();
+        flatten_table(String::new(), table, strings)?;
+        Ok(())
+    }
+    pub fn insert(
+        &mut self,
+        locale: impl Into<String>,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) {
+        self.strings
+            .entry(locale.into())
+            .or_default()
+            .insert(key.into(), value.into());
+    }
+    pub fn default_locale(&self) -> &str {
+        self.default_locale.as_str()
+    }
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.strings.keys().map(|x| x.as_str())
+    }
+    pub fn get(&self, locale: &str, key: &str) -> Option<&str> {
+        self.strings
+            .get(locale)
+            .and_then(|strings| strings.get(key))
+            .map(|x| x.as_str())
+    }
+    pub fn resolve(&self, locales: &[&str], key: &str) -> Option<&str> {
+        locales
+            .iter()
+            .copied()
+            .chain(std::iter::once(self.default_locale.as_str()))
+            .flat_map(|locale| [Some(locale), locale.split_once('-').map(|(lang, _)| lang)])
+            .flatten()
+            .find_map(|locale| self.get(locale, key))
+    }
+    pub fn translate(&self, locales: &[&str], key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let Some(template) = self.resolve(locales, key) else {
+            if self
+                .reported_missing
+                .lock()
+                .unwrap()
+                .insert(String::from(key))
+            {
+                tracing::warn!("missing translation for '{}'", key);
+            }
+            return String::from(key);
+        };
+        interpolate(template, args)
+    }
+    pub fn translate_or(
+        &self,
+        locales: &[&str],
+        key: &str,
+        fallback: &str,
+        args: &[(&str, &dyn Display)],
+    ) -> String {
+        interpolate(self.resolve(locales, key).unwrap_or(fallback), args)
+    }
+    pub fn localize(&self, key: &str) -> Option<HashMap<String, String>> {
+        let map: HashMap<String, String> = self
+            .strings
+            .iter()
+            .filter(|(locale, _)| **locale != self.default_locale)
+            .filter_map(|(locale, strings)| Some((locale.clone(), strings.get(key)?.clone())))
+            .collect();
+        if map.is_empty() {
+            None
+        } else {
+            Some(map)
+        }
+    }
+    pub fn apply(&self, command: &mut ApplicationCommand) {
+        let path = command.name.clone();
+        merge(
+            &mut command.name_localizations,
+            self.localize(&name_key(&path)),
+        );
+        merge(
+            &mut command.description_localizations,
+            self.localize(&description_key(&path)),
+        );
+        for option in command.options.iter_mut() {
+            self.apply_option(&path, option);
+        }
+    }
+    fn apply_option(&self, path: &str, option: &mut CommandOption) {
+        match option.kind {
+            CommandOptionType::SubCommand | CommandOptionType::SubCommandGroup => {
+                let path = format!("{path} {}", option.name);
+                merge(
+                    &mut option.name_localizations,
+                    self.localize(&name_key(&path)),
+                );
+                merge(
+                    &mut option.description_localizations,
+                    self.localize(&description_key(&path)),
+                );
+                for sub in option.options.iter_mut().flatten() {
+                    self.apply_option(&path, sub);
+                }
+            }
+            _ => {
+                let parameter = parameter_key(path, &option.name);
+                option.name_localizations = self.localize(&format!("{parameter}.name"));
+                option.description_localizations =
+                    self.localize(&format!("{parameter}.description"));
+                for choice in option.choices.iter_mut().flatten() {
+                    choice.name_localizations =
+                        self.localize(&format!("{parameter}.choices.{}", choice.name));
+                }
+            }
+        }
+    }
+    pub fn validate(&self, commands: &[ApplicationCommand]) -> Vec<MissingTranslation> {
+        let mut keys = BTreeSet::new();
+        for command in commands {
+            keys.insert(name_key(&command.name));
+            if !command.description.is_empty() {
+                keys.insert(description_key(&command.name));
+            }
+            for option in command.options.iter() {
+                collect_option_keys(&command.name, option, &mut keys);
+            }
+        }
+
+        let mut locales: Vec<&String> = self
+            .strings
+            .keys()
+            .filter(|locale| **locale != self.default_locale)
+            .collect();
+        locales.sort();
+
+        let mut missing = Vec::new();
+        for locale in locales {
+            let strings = &self.strings[locale];
+            for key in keys.iter().filter(|key| !strings.contains_key(*key)) {
+                missing.push(MissingTranslation {
+                    locale: locale.clone(),
+                    key: key.clone(),
+                });
+            }
+        }
+        missing
+    }
+}
+
+pub(crate) fn interpolate(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let name = &after[..end];
+        match args.iter().find(|(arg, _)| *arg == name) {
+            Some((_, value)) => output.push_str(&value.to_string()),
+            None => output.push_str(&rest[start..start + end + 2]),
+        }
+        rest = &after[end + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+fn flatten_table(
+    prefix: String,
+    table: toml::Table,
+    strings: &mut HashMap<String, String>,
+) -> Result<(), LocalizationError> {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            toml::Value::String(value) => {
+                strings.insert(key, value);
+            }
+            toml::Value::Table(table) => flatten_table(key, table, strings)?,
+            _ => return Err(LocalizationError::InvalidValue(key)),
+        }
+    }
+    Ok(())
+}
+
+fn collect_option_keys(path: &str, option: &CommandOption, keys: &mut BTreeSet<String>) {
+    match option.kind {
+        CommandOptionType::SubCommand | CommandOptionType::SubCommandGroup => {
+            let path = format!("{path} {}", option.name);
+            keys.insert(name_key(&path));
+            keys.insert(description_key(&path));
+            for sub in option.options.iter().flatten() {
+                collect_option_keys(&path, sub, keys);
+            }
+        }
+        _ => {
+            let parameter = parameter_key(path, &option.name);
+            keys.insert(format!("{parameter}.name"));
+            keys.insert(format!("{parameter}.description"));
+            for choice in option.choices.iter().flatten() {
+                keys.insert(format!("{parameter}.choices.{}", choice.name));
+            }
+        }
+    }
+}
+
+fn merge(
+    localizations: &mut Option<HashMap<String, String>>,
+    translations: Option<HashMap<String, String>>,
+) {
+    match (localizations.as_mut(), translations) {
+        (Some(localizations), Some(translations)) => localizations.extend(translations),
+        (None, translations) => *localizations = translations,
+        (Some(_), None) => {}
+    }
+}
+
+fn name_key(path: &str) -> String {
+    format!("commands.{path}.name")
+}
+
+fn description_key(path: &str) -> String {
+    format!("commands.{path}.description")
+}
+
+fn parameter_key(path: &str, parameter: &str) -> String {
+    format!("commands.{path}.parameters.{parameter}")
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MissingTranslation {
+    pub locale: String,
+    pub key: String,
+}
+impl fmt::Display for MissingTranslation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "missing '{}' translation for '{}'",
+            self.locale, self.key
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum LocalizationError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, toml::de::Error),
+    InvalidValue(String),
+}
+impl fmt::Display for LocalizationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, e) => write!(f, "failed to read '{}': {e}", path.display()),
+            Self::Parse(path, e) => write!(f, "failed to parse '{}': {e}", path.display()),
+            Self::InvalidValue(key) => write!(f, "expected a string or table at '{key}'"),
+        }
+    }
+}
+impl std::error::Error for LocalizationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(_, e) => Some(e),
+            Self::Parse(_, e) => Some(e),
+            Self::InvalidValue(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolates_placeholders() {
+        let count = 3;
+        let args: &[(&str, &dyn Display)] = &[("count", &count), ("name", &"wab")];
+        assert_eq!(
+            interpolate("{name} ran {count} times", args),
+            "wab ran 3 times"
+        );
+        assert_eq!(interpolate("{count}{count}", args), "33");
+        assert_eq!(
+            interpolate("{missing} and {name}", args),
+            "{missing} and wab"
+        );
+        assert_eq!(interpolate("{} {name", args), "{} {name");
+        assert_eq!(interpolate("no placeholders", &[]), "no placeholders");
+    }
+
+    #[test]
+    fn translates_with_fallbacks() {
+        let mut localizations = Localizations::new("en-US");
+        localizations.insert("en-US", "greeting", "Hello {name}");
+        localizations.insert("en-US", "farewell", "Bye");
+        localizations.insert("de", "greeting", "Hallo {name}");
+        localizations.insert("fr", "farewell", "Au revoir");
+        let args: &[(&str, &dyn Display)] = &[("name", &"wab")];
+
+        assert_eq!(
+            localizations.translate(&["de"], "greeting", args),
+            "Hallo wab"
+        );
+        assert_eq!(
+            localizations.translate(&["de-AT"], "greeting", args),
+            "Hallo wab"
+        );
+        assert_eq!(
+            localizations.translate(&["fr", "de"], "greeting", args),
+            "Hallo wab"
+        );
+        assert_eq!(
+            localizations.translate(&["de", "fr"], "farewell", args),
+            "Au revoir"
+        );
+        assert_eq!(
+            localizations.translate(&["ja"], "greeting", args),
+            "Hello wab"
+        );
+        assert_eq!(localizations.translate(&[], "farewell", args), "Bye");
+        assert_eq!(localizations.translate(&["de"], "missing", args), "missing");
+    }
+}
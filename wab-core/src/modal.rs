@@ -0,0 +1,35 @@
+use crate::{BoxedFuture, CommandResult, ModalContext};
+use std::collections::HashMap;
+use twilight_model::application::interaction::modal::ModalInteractionData;
+use twilight_model::channel::message::Component;
+use twilight_model::http::interaction::InteractionResponseData;
+
+pub type ModalFunction = fn(ModalContext, Vec<String>) -> BoxedFuture<CommandResult>;
+
+pub struct ModalHandler {
+    pub prefix: String,
+    pub function: ModalFunction,
+}
+
+pub trait Modal: Sized {
+    fn title() -> String;
+    fn create_components() -> Vec<Component>;
+    fn parse(fields: &HashMap<String, String>) -> Option<Self>;
+
+    fn create_response_data(custom_id: impl Into<String>) -> InteractionResponseData {
+        InteractionResponseData {
+            components: Some(Self::create_components()),
+            custom_id: Some(custom_id.into()),
+            title: Some(Self::title()),
+            ..Default::default()
+        }
+    }
+}
+
+pub fn modal_fields(data: &ModalInteractionData) -> HashMap<String, String> {
+    data.components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .filter_map(|c| Some((c.custom_id.clone(), c.value.clone()?)))
+        .collect()
+}
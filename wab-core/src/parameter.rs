@@ -1,6 +1,15 @@
+use std::collections::HashMap;
+use std::fmt;
 use twilight_model::application::command::{
     CommandOptionChoice, CommandOptionChoiceValue, CommandOptionType, CommandOptionValue,
 };
+use twilight_model::channel::ChannelType;
+
+use crate::localization::{interpolate, DEFAULT_SUFFIX};
+use crate::{Argument, AutocompleteContext, BoxedFuture};
+
+pub type AutocompleteFunction =
+    fn(AutocompleteContext, String, HashMap<String, Argument>) -> BoxedFuture<Vec<ParameterChoice>>;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum ParameterType {
@@ -8,6 +17,11 @@ pub enum ParameterType {
     Integer,
     Boolean,
     Float,
+    User,
+    Channel,
+    Role,
+    Mentionable,
+    Attachment,
 }
 impl ParameterType {
     pub fn create_twilight_option_type(&self) -> CommandOptionType {
@@ -16,6 +30,11 @@ impl ParameterType {
             Self::Integer => CommandOptionType::Integer,
             Self::Float => CommandOptionType::Number,
             Self::Boolean => CommandOptionType::Boolean,
+            Self::User => CommandOptionType::User,
+            Self::Channel => CommandOptionType::Channel,
+            Self::Role => CommandOptionType::Role,
+            Self::Mentionable => CommandOptionType::Mentionable,
+            Self::Attachment => CommandOptionType::Attachment,
         }
     }
 }
@@ -39,8 +58,8 @@ impl ParameterChoice {
     }
     pub fn create_twilight_choice(&self) -> CommandOptionChoice {
         let value = match &self.value {
-            ParameterChoiceType::Integer(v) => CommandOptionChoiceValue::Integer(v.clone()),
-            ParameterChoiceType::Float(v) => CommandOptionChoiceValue::Number(v.clone()),
+            ParameterChoiceType::Integer(v) => CommandOptionChoiceValue::Integer(*v),
+            ParameterChoiceType::Float(v) => CommandOptionChoiceValue::Number(*v),
             ParameterChoiceType::String(v) => CommandOptionChoiceValue::String(v.clone()),
         };
         CommandOptionChoice {
@@ -57,6 +76,10 @@ impl ParameterChoice {
     }
 }
 
+pub trait Choices: Sized {
+    fn choices() -> Vec<ParameterChoice>;
+}
+
 #[derive(Debug)]
 pub struct Parameter {
     name: String,
@@ -70,6 +93,9 @@ pub struct Parameter {
     max_value_number: Option<f64>,
     min_length: Option<i32>,
     max_length: Option<i32>,
+    channel_types: Vec<ChannelType>,
+    autocomplete: Option<AutocompleteFunction>,
+    default_value: Option<String>,
 }
 
 impl Parameter {
@@ -106,6 +132,42 @@ impl Parameter {
     pub fn max_length(&self) -> &Option<i32> {
         &self.max_length
     }
+    pub fn channel_types(&self) -> &Vec<ChannelType> {
+        &self.channel_types
+    }
+    pub fn autocomplete(&self) -> Option<AutocompleteFunction> {
+        self.autocomplete
+    }
+    pub fn default_value(&self) -> Option<&str> {
+        self.default_value.as_deref()
+    }
+    pub fn default_choice(&self) -> Option<&ParameterChoice> {
+        let default_value = self.default_value.as_deref()?;
+        self.choices.iter().find(|choice| {
+            choice.name == default_value
+                || match &choice.value {
+                    ParameterChoiceType::String(value) => value == default_value,
+                    ParameterChoiceType::Integer(value) => default_value.parse() == Ok(*value),
+                    ParameterChoiceType::Float(value) => default_value.parse() == Ok(*value),
+                }
+        })
+    }
+    pub fn default_label(&self) -> Option<&str> {
+        match self.default_choice() {
+            Some(choice) => Some(choice.name.as_str()),
+            None => self.default_value.as_deref(),
+        }
+    }
+    pub fn default_suffix(&self) -> Option<String> {
+        let label = self.default_label()?;
+        Some(interpolate(DEFAULT_SUFFIX, &[("value", &label)]))
+    }
+    pub fn create_twilight_description(&self) -> String {
+        match self.default_suffix() {
+            Some(suffix) => format!("{}{suffix}", self.description),
+            None => self.description.clone(),
+        }
+    }
     pub fn create_twilight_choices(&self) -> Option<Vec<CommandOptionChoice>> {
         if self.choices.is_empty() {
             return None;
@@ -117,37 +179,29 @@ impl Parameter {
                 .collect(),
         )
     }
+    pub fn create_twilight_channel_types(&self) -> Option<Vec<ChannelType>> {
+        if self.channel_types.is_empty() {
+            return None;
+        }
+        Some(self.channel_types.clone())
+    }
     pub fn create_twilight_max_value(&self) -> Option<CommandOptionValue> {
-        if let Some(value) = self.max_value_int {
-            Some(CommandOptionValue::Integer(value.clone()))
-        } else if let Some(value) = self.max_value_number {
-            Some(CommandOptionValue::Number(value.clone()))
-        } else {
-            None
+        match self.max_value_int {
+            Some(value) => Some(CommandOptionValue::Integer(value)),
+            None => self.max_value_number.map(CommandOptionValue::Number),
         }
     }
     pub fn create_twilight_min_value(&self) -> Option<CommandOptionValue> {
-        if let Some(value) = self.min_value_int {
-            Some(CommandOptionValue::Integer(value.clone()))
-        } else if let Some(value) = self.min_value_number {
-            Some(CommandOptionValue::Number(value.clone()))
-        } else {
-            None
+        match self.min_value_int {
+            Some(value) => Some(CommandOptionValue::Integer(value)),
+            None => self.min_value_number.map(CommandOptionValue::Number),
         }
     }
     pub fn create_twilight_max_length(&self) -> Option<u16> {
-        if let Some(len) = self.max_length {
-            Some(u16::try_from(len.clone()).unwrap())
-        } else {
-            None
-        }
+        self.max_length.and_then(|len| u16::try_from(len).ok())
     }
     pub fn create_twilight_min_length(&self) -> Option<u16> {
-        if let Some(len) = self.min_length {
-            Some(u16::try_from(len.clone()).unwrap())
-        } else {
-            None
-        }
+        self.min_length.and_then(|len| u16::try_from(len).ok())
     }
     pub fn builder() -> ParameterBuilder {
         ParameterBuilder::new()
@@ -167,6 +221,9 @@ pub struct ParameterBuilder {
     max_value_number: Option<f64>,
     min_length: Option<i32>,
     max_length: Option<i32>,
+    channel_types: Vec<ChannelType>,
+    autocomplete: Option<AutocompleteFunction>,
+    default_value: Option<String>,
 }
 impl ParameterBuilder {
     fn new() -> Self {
@@ -220,34 +277,32 @@ impl ParameterBuilder {
         self.max_length = max_length;
         self
     }
-    pub fn build(&mut self) -> Parameter {
-        assert!(!self.description.is_empty() && self.description.len() <= 100);
-        assert!(!self.name.is_empty() && self.name.len() <= 32);
-
-        if let Some(min_length) = self.min_length {
-            assert!(0 <= min_length && min_length <= 6000);
-        }
-        if let Some(max_length) = self.max_length {
-            assert!(0 <= max_length && max_length <= 6000);
-        }
-        if let (Some(min_value_int), Some(max_value_int)) = (self.min_value_int, self.max_value_int)
-        {
-            assert!(min_value_int <= max_value_int);
-        }
-        if let (Some(min_value_number), Some(max_value_number)) =
-            (self.min_value_number, self.max_value_number)
-        {
-            assert!(min_value_number <= max_value_number);
-        }
-        if let (Some(min_length), Some(max_length)) = (self.min_length, self.max_length) {
-            assert!(min_length <= max_length);
-        }
-        assert!(self.choices.len() <= 25);
-
-        Parameter {
+    pub fn channel_type(&mut self, channel_type: ChannelType) -> &mut Self {
+        self.channel_types.push(channel_type);
+        self
+    }
+    pub fn channel_types(&mut self, channel_types: Vec<ChannelType>) -> &mut Self {
+        self.channel_types = channel_types;
+        self
+    }
+    pub fn autocomplete(&mut self, autocomplete: Option<AutocompleteFunction>) -> &mut Self {
+        self.autocomplete = autocomplete;
+        self
+    }
+    pub fn default_value(&mut self, default_value: Option<String>) -> &mut Self {
+        self.default_value = default_value;
+        self
+    }
+    pub fn build(&mut self) -> Result<Parameter, ParameterError> {
+        let Some(kind) = self.kind.clone() else {
+            return Err(ParameterError {
+                name: self.name.clone(),
+            });
+        };
+        Ok(Parameter {
             name: self.name.clone(),
             description: self.description.clone(),
-            kind: self.kind.clone().unwrap(),
+            kind,
             required: self.required,
             choices: self.choices.clone(),
             min_value_int: self.min_value_int,
@@ -256,6 +311,25 @@ impl ParameterBuilder {
             max_value_number: self.max_value_number,
             min_length: self.min_length,
             max_length: self.max_length,
-        }
+            channel_types: self.channel_types.clone(),
+            autocomplete: self.autocomplete,
+            default_value: self.default_value.clone(),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ParameterError {
+    name: String,
+}
+impl ParameterError {
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+impl fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "parameter '{}' has no kind", self.name)
     }
 }
+impl std::error::Error for ParameterError {}
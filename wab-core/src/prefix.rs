@@ -0,0 +1,529 @@
+use crate::argument::parse_mention;
+use crate::parameter::{Parameter, ParameterChoiceType, ParameterType};
+use crate::{Argument, Command, CommandPermissions, Error};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use twilight_cache_inmemory::InMemoryCache;
+use twilight_model::application::interaction::application_command::{
+    InteractionChannel, InteractionMember,
+};
+use twilight_model::channel::Message;
+use twilight_model::guild::Permissions;
+use twilight_model::id::{
+    marker::{ChannelMarker, GenericMarker, GuildMarker, RoleMarker, UserMarker},
+    Id,
+};
+use twilight_model::user::User;
+use typemap_rev::TypeMapKey;
+
+pub struct Prefixes {
+    default: Option<String>,
+    guilds: RwLock<HashMap<Id<GuildMarker>, String>>,
+}
+impl Prefixes {
+    pub fn new(default: Option<String>, guilds: HashMap<Id<GuildMarker>, String>) -> Self {
+        Self {
+            default,
+            guilds: RwLock::new(guilds),
+        }
+    }
+    pub fn default_prefix(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+    pub fn get(&self, guild_id: Option<Id<GuildMarker>>) -> Option<String> {
+        guild_id
+            .and_then(|id| self.guilds.read().unwrap().get(&id).cloned())
+            .or_else(|| self.default.clone())
+    }
+    pub fn set(&self, guild_id: Id<GuildMarker>, prefix: impl Into<String>) {
+        self.guilds.write().unwrap().insert(guild_id, prefix.into());
+    }
+    pub fn reset(&self, guild_id: Id<GuildMarker>) {
+        self.guilds.write().unwrap().remove(&guild_id);
+    }
+}
+impl TypeMapKey for Prefixes {
+    type Value = Arc<Prefixes>;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub value: String,
+    pub quoted: bool,
+    pub start: usize,
+    pub end: usize,
+}
+
+pub fn split_arguments(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut value = String::new();
+        let mut quote = None;
+        let mut quoted = false;
+        while let Some(&(_, c)) = chars.peek() {
+            match (quote, c) {
+                (None, c) if c.is_whitespace() => break,
+                (None, '"' | '\'') if value.is_empty() => {
+                    quote = Some(c);
+                    quoted = true;
+                }
+                (Some(q), c) if c == q => {
+                    chars.next();
+                    break;
+                }
+                (_, '\\') => {
+                    chars.next();
+                    if let Some(&(_, escaped)) = chars.peek() {
+                        value.push(escaped);
+                    }
+                }
+                (_, c) => value.push(c),
+            }
+            chars.next();
+        }
+        let end = chars.peek().map_or(text.len(), |&(i, _)| i);
+        tokens.push(Token {
+            value,
+            quoted,
+            start,
+            end,
+        });
+    }
+    tokens
+}
+
+pub fn parse_arguments(
+    command: &Command,
+    text: &str,
+    message: &Message,
+    cache: &InMemoryCache,
+) -> Result<HashMap<String, Argument>, Box<Error>> {
+    let tokens = split_arguments(text);
+    let mut tokens = tokens.iter().peekable();
+    let mut attachments = message.attachments.iter();
+    let mut args = HashMap::new();
+    let parameters = command.parameters();
+    for (i, parameter) in parameters.iter().enumerate() {
+        let argument = if *parameter.kind() == ParameterType::Attachment {
+            attachments.next().map(|attachment| Argument::Attachment {
+                id: attachment.id,
+                attachment: Some(attachment.clone()),
+            })
+        } else if let Some(token) = tokens.next() {
+            let last = i + 1 == parameters.len();
+            let rest = last && *parameter.kind() == ParameterType::String;
+            let value = if !rest || tokens.peek().is_none() {
+                token.value.clone()
+            } else if token.quoted {
+                tokens.by_ref().for_each(drop);
+                format!("{}{}", token.value, text[token.end..].trim_end())
+            } else {
+                tokens.by_ref().for_each(drop);
+                String::from(text[token.start..].trim_end())
+            };
+            let argument = parse_argument(parameter, &value, message, cache)
+                .map_err(|reason| Error::argument(parameter.name(), reason))?;
+            Some(argument)
+        } else {
+            None
+        };
+        match argument {
+            Some(argument) => {
+                args.insert(String::from(parameter.name()), argument);
+            }
+            None if *parameter.required() => {
+                return Err(Box::new(Error::argument(
+                    parameter.name(),
+                    "missing required argument",
+                )));
+            }
+            None => {}
+        }
+    }
+    if let Some(token) = tokens.next() {
+        return Err(Box::new(Error::user(format!(
+            "Unexpected argument `{}`.",
+            token.value
+        ))));
+    }
+    Ok(args)
+}
+
+fn parse_argument(
+    parameter: &Parameter,
+    text: &str,
+    message: &Message,
+    cache: &InMemoryCache,
+) -> Result<Argument, String> {
+    if !parameter.choices().is_empty() {
+        return parameter
+            .choices()
+            .iter()
+            .find(|choice| match choice.value() {
+                ParameterChoiceType::String(value) => value == text,
+                ParameterChoiceType::Integer(value) => text.parse() == Ok(*value),
+                ParameterChoiceType::Float(value) => text.parse() == Ok(*value),
+            } || choice.name().eq_ignore_ascii_case(text))
+            .map(|choice| match choice.value() {
+                ParameterChoiceType::String(value) => Argument::String(value.clone()),
+                ParameterChoiceType::Integer(value) => Argument::Integer(*value),
+                ParameterChoiceType::Float(value) => Argument::Float(*value),
+            })
+            .ok_or_else(|| format!("'{text}' is not one of the available choices"));
+    }
+    let argument = match parameter.kind() {
+        ParameterType::String => {
+            let length = text.chars().count() as i32;
+            match (*parameter.min_length(), *parameter.max_length()) {
+                (Some(min), _) if length < min => {
+                    return Err(format!("must be at least {min} characters"));
+                }
+                (_, Some(max)) if length > max => {
+                    return Err(format!("must be at most {max} characters"));
+                }
+                _ => Argument::String(String::from(text)),
+            }
+        }
+        ParameterType::Integer => {
+            let value: i64 = text
+                .parse()
+                .map_err(|_| format!("'{text}' is not a valid integer"))?;
+            check_range(
+                value,
+                *parameter.min_value_int(),
+                *parameter.max_value_int(),
+            )?;
+            Argument::Integer(value)
+        }
+        ParameterType::Float => {
+            let value: f64 = text
+                .parse()
+                .map_err(|_| format!("'{text}' is not a valid number"))?;
+            check_range(
+                value,
+                *parameter.min_value_number(),
+                *parameter.max_value_number(),
+            )?;
+            Argument::Float(value)
+        }
+        ParameterType::Boolean => match text.to_lowercase().as_str() {
+            "true" | "yes" | "on" => Argument::Boolean(true),
+            "false" | "no" | "off" => Argument::Boolean(false),
+            _ => return Err(format!("'{text}' is not true or false")),
+        },
+        ParameterType::User => {
+            let id = parse_mention(text, &["<@!", "<@"])?;
+            Argument::User {
+                id,
+                user: find_user(id, message, cache),
+                member: find_member(id, message, cache),
+            }
+        }
+        ParameterType::Channel => {
+            let id: Id<ChannelMarker> = parse_mention(text, &["<#"])?;
+            let channel = cache.channel(id).map(|channel| InteractionChannel {
+                id,
+                kind: channel.kind,
+                name: channel.name.clone().unwrap_or_default(),
+                parent_id: channel.parent_id,
+                permissions: cache
+                    .permissions()
+                    .in_channel(message.author.id, id)
+                    .unwrap_or(Permissions::empty()),
+                thread_metadata: channel.thread_metadata.clone(),
+            });
+            if let Some(channel) = &channel {
+                let types = parameter.channel_types();
+                if !types.is_empty() && !types.contains(&channel.kind) {
+                    return Err(format!("<#{id}> is not an allowed channel type"));
+                }
+            }
+            Argument::Channel { id, channel }
+        }
+        ParameterType::Role => {
+            let id: Id<RoleMarker> = parse_mention(text, &["<@&"])?;
+            Argument::Role {
+                id,
+                role: cache.role(id).map(|role| role.resource().clone()),
+            }
+        }
+        ParameterType::Mentionable => {
+            let id: Id<GenericMarker> = parse_mention(text, &["<@&", "<@!", "<@"])?;
+            let role = cache.role(id.cast()).map(|role| role.resource().clone());
+            Argument::Mentionable {
+                id,
+                user: role
+                    .is_none()
+                    .then(|| find_user(id.cast(), message, cache))
+                    .flatten(),
+                member: role
+                    .is_none()
+                    .then(|| find_member(id.cast(), message, cache))
+                    .flatten(),
+                role,
+            }
+        }
+        ParameterType::Attachment => return Err(String::from("expected an attachment")),
+    };
+    Ok(argument)
+}
+
+fn find_user(id: Id<UserMarker>, message: &Message, cache: &InMemoryCache) -> Option<User> {
+    let mention = message.mentions.iter().find(|mention| mention.id == id);
+    match mention {
+        Some(mention) => Some(User {
+            accent_color: None,
+            avatar: mention.avatar,
+            avatar_decoration: None,
+            banner: None,
+            bot: mention.bot,
+            discriminator: mention.discriminator,
+            email: None,
+            flags: None,
+            global_name: None,
+            id: mention.id,
+            locale: None,
+            mfa_enabled: None,
+            name: mention.name.clone(),
+            premium_type: None,
+            public_flags: Some(mention.public_flags),
+            system: None,
+            verified: None,
+        }),
+        None => cache.user(id).map(|user| user.clone()),
+    }
+}
+
+fn find_member(
+    id: Id<UserMarker>,
+    message: &Message,
+    cache: &InMemoryCache,
+) -> Option<InteractionMember> {
+    let guild_id = message.guild_id?;
+    let permissions = cache
+        .permissions()
+        .in_channel(id, message.channel_id)
+        .unwrap_or(Permissions::empty());
+    let mention = message
+        .mentions
+        .iter()
+        .find(|mention| mention.id == id)
+        .and_then(|mention| mention.member.as_ref());
+    if let Some(member) = mention {
+        return Some(InteractionMember {
+            avatar: member.avatar,
+            communication_disabled_until: member.communication_disabled_until,
+            flags: member.flags,
+            joined_at: member.joined_at,
+            nick: member.nick.clone(),
+            pending: false,
+            permissions,
+            premium_since: member.premium_since,
+            roles: member.roles.clone(),
+        });
+    }
+    cache.member(guild_id, id).map(|member| InteractionMember {
+        avatar: member.avatar(),
+        communication_disabled_until: member.communication_disabled_until(),
+        flags: member.flags(),
+        joined_at: member.joined_at(),
+        nick: member.nick().map(String::from),
+        pending: member.pending(),
+        permissions,
+        premium_since: member.premium_since(),
+        roles: member.roles().to_vec(),
+    })
+}
+
+fn check_range<T: PartialOrd + std::fmt::Display>(
+    value: T,
+    min: Option<T>,
+    max: Option<T>,
+) -> Result<(), String> {
+    match (min, max) {
+        (Some(min), _) if value < min => Err(format!("must be at least {min}")),
+        (_, Some(max)) if value > max => Err(format!("must be at most {max}")),
+        _ => Ok(()),
+    }
+}
+
+pub fn permitted(
+    cache: &InMemoryCache,
+    permissions: &CommandPermissions,
+    message: &Message,
+) -> bool {
+    if message.guild_id.is_none() {
+        return permissions.dm_permission != Some(false);
+    }
+    if permissions.nsfw == Some(true) {
+        let nsfw = cache
+            .channel(message.channel_id)
+            .is_some_and(|channel| channel.nsfw == Some(true));
+        if !nsfw {
+            return false;
+        }
+    }
+    match permissions.default_member_permissions {
+        Some(required) => {
+            let required = if required.is_empty() {
+                Permissions::ADMINISTRATOR
+            } else {
+                required
+            };
+            cache
+                .permissions()
+                .in_channel(message.author.id, message.channel_id)
+                .is_ok_and(|actual| actual.contains(required))
+        }
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BoxedFuture, CommandContext, CommandResult};
+
+    fn noop(_ctx: CommandContext, _args: HashMap<String, Argument>) -> BoxedFuture<CommandResult> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn parameter(name: &str, kind: ParameterType, required: bool) -> Parameter {
+        Parameter::builder()
+            .name(name)
+            .description("a parameter")
+            .kind(kind)
+            .required(required)
+            .build()
+            .unwrap()
+    }
+
+    fn message(content: &str) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "attachments": [],
+            "author": {
+                "avatar": null,
+                "discriminator": "0",
+                "id": "2",
+                "username": "author",
+            },
+            "channel_id": "3",
+            "content": content,
+            "edited_timestamp": null,
+            "embeds": [],
+            "flags": 0,
+            "guild_id": "4",
+            "id": "5",
+            "mention_everyone": false,
+            "mention_roles": [],
+            "mentions": [{
+                "avatar": null,
+                "discriminator": "0",
+                "id": "6",
+                "username": "mentioned",
+                "public_flags": 0,
+                "member": {
+                    "deaf": false,
+                    "flags": 0,
+                    "joined_at": "2024-01-01T00:00:00.000000+00:00",
+                    "mute": false,
+                    "nick": "nick",
+                    "roles": [],
+                },
+            }],
+            "pinned": false,
+            "timestamp": "2024-01-01T00:00:00.000000+00:00",
+            "tts": false,
+            "type": 0,
+        }))
+        .unwrap()
+    }
+
+    fn parse(command: &Command, text: &str) -> Result<HashMap<String, Argument>, Box<Error>> {
+        parse_arguments(command, text, &message(text), &InMemoryCache::new())
+    }
+
+    fn string(args: &HashMap<String, Argument>, name: &str) -> String {
+        match &args[name] {
+            Argument::String(x) => x.clone(),
+            _ => panic!("'{name}' is not a string"),
+        }
+    }
+
+    #[test]
+    fn splits_quoted_and_escaped_tokens() {
+        let values: Vec<String> = split_arguments(r#"  one "two three" 'f"our' fi\ ve "" "#)
+            .into_iter()
+            .map(|token| token.value)
+            .collect();
+        assert_eq!(values, ["one", "two three", "f\"our", "fi ve", ""]);
+
+        let tokens = split_arguments(r#"a "b c" d"#);
+        assert_eq!(
+            tokens[1],
+            Token {
+                value: String::from("b c"),
+                quoted: true,
+                start: 2,
+                end: 7,
+            }
+        );
+        assert!(!tokens[2].quoted);
+        assert!(split_arguments("   ").is_empty());
+    }
+
+    #[test]
+    fn parses_typed_arguments() {
+        let command = Command::builder()
+            .name("set")
+            .description("a command")
+            .parameter(parameter("count", ParameterType::Integer, true))
+            .parameter(parameter("enabled", ParameterType::Boolean, true))
+            .parameter(parameter("user", ParameterType::User, false))
+            .function(noop)
+            .build();
+
+        let args = parse(&command, "5 yes <@!6>").unwrap();
+        assert!(matches!(args["count"], Argument::Integer(5)));
+        assert!(matches!(args["enabled"], Argument::Boolean(true)));
+        let Argument::User { id, user, member } = &args["user"] else {
+            panic!("expected a user");
+        };
+        assert_eq!(id.get(), 6);
+        assert_eq!(user.as_ref().unwrap().name, "mentioned");
+        assert_eq!(member.as_ref().unwrap().nick.as_deref(), Some("nick"));
+
+        let args = parse(&command, "5 no").unwrap();
+        assert!(!args.contains_key("user"));
+
+        let error = parse(&command, "five yes").err().unwrap();
+        assert!(matches!(*error, Error::Argument { ref name, .. } if name == "count"));
+        let error = parse(&command, "5").err().unwrap();
+        assert!(matches!(*error, Error::Argument { ref name, .. } if name == "enabled"));
+        let error = parse(&command, "5 yes 6 extra").err().unwrap();
+        assert!(matches!(*error, Error::User(_)));
+    }
+
+    #[test]
+    fn last_string_takes_the_rest_of_the_line() {
+        let command = Command::builder()
+            .name("say")
+            .description("a command")
+            .parameter(parameter("channel", ParameterType::Integer, true))
+            .parameter(parameter("text", ParameterType::String, true))
+            .function(noop)
+            .build();
+
+        let args = parse(&command, "1 hello   \"big\" world  ").unwrap();
+        assert_eq!(string(&args, "text"), "hello   \"big\" world");
+        let args = parse(&command, "1 \"hello there\" world").unwrap();
+        assert_eq!(string(&args, "text"), "hello there world");
+        let args = parse(&command, "1 \"hello there\"").unwrap();
+        assert_eq!(string(&args, "text"), "hello there");
+    }
+}
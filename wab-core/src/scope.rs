@@ -0,0 +1,53 @@
+use std::fmt;
+use std::str::FromStr;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum CommandScope {
+    #[default]
+    Global,
+    Guilds(Vec<Id<GuildMarker>>),
+}
+impl CommandScope {
+    pub fn targets(&self) -> Vec<RegistrationTarget> {
+        match self {
+            Self::Global => vec![RegistrationTarget::Global],
+            Self::Guilds(guilds) => guilds
+                .iter()
+                .map(|id| RegistrationTarget::Guild(*id))
+                .collect(),
+        }
+    }
+    pub fn includes(&self, guild_id: Option<Id<GuildMarker>>) -> bool {
+        match self {
+            Self::Global => true,
+            Self::Guilds(guilds) => guild_id.is_some_and(|id| guilds.contains(&id)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RegistrationTarget {
+    Global,
+    Guild(Id<GuildMarker>),
+}
+impl fmt::Display for RegistrationTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Global => write!(f, "global"),
+            Self::Guild(id) => write!(f, "{id}"),
+        }
+    }
+}
+impl FromStr for RegistrationTarget {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "global" => Ok(Self::Global),
+            _ => s
+                .parse()
+                .map(Self::Guild)
+                .map_err(|_| format!("'{s}' is not a registration target")),
+        }
+    }
+}
@@ -1,8 +1,8 @@
 use tokio::sync::RwLock;
-use typemap_rev::{TypeMapKey, TypeMap};
+use typemap_rev::{TypeMap, TypeMapKey};
 
 pub struct State {
-    pub storage: RwLock<TypeMap>
+    pub storage: RwLock<TypeMap>,
 }
 impl State {
     pub async fn get<T>(&self) -> <T as TypeMapKey>::Value
@@ -12,5 +12,5 @@ impl State {
     {
         let read = self.storage.read().await;
         read.get::<T>().expect("a").clone()
-    }  
+    }
 }
@@ -0,0 +1,464 @@
+use crate::RegistrationTarget;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+use twilight_http::{request::Request, routing::Route};
+use twilight_model::application::command::{
+    Command as ApplicationCommand, CommandOption, CommandOptionChoice, CommandOptionType,
+    CommandOptionValue, CommandType,
+};
+use twilight_model::id::{
+    marker::{ApplicationMarker, CommandMarker},
+    Id,
+};
+
+#[derive(Clone, Debug)]
+pub struct CommandUpdate {
+    pub id: Id<CommandMarker>,
+    pub command: ApplicationCommand,
+    pub changes: Vec<&'static str>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CommandDiff {
+    pub create: Vec<ApplicationCommand>,
+    pub update: Vec<CommandUpdate>,
+    pub delete: Vec<ApplicationCommand>,
+    pub unchanged: Vec<String>,
+}
+impl CommandDiff {
+    pub fn new(existing: &[ApplicationCommand], desired: &[ApplicationCommand]) -> Self {
+        let mut diff = Self::default();
+
+        let mut existing_map: HashMap<(CommandType, &str), &ApplicationCommand> = existing
+            .iter()
+            .map(|c| ((c.kind, c.name.as_str()), c))
+            .collect();
+
+        for command in desired {
+            match existing_map.remove(&(command.kind, command.name.as_str())) {
+                Some(current) => {
+                    let changes = command_changes(current, command);
+                    match current.id {
+                        Some(id) if !changes.is_empty() => diff.update.push(CommandUpdate {
+                            id,
+                            command: command.clone(),
+                            changes,
+                        }),
+                        Some(_) => diff.unchanged.push(command.name.clone()),
+                        None => diff.create.push(command.clone()),
+                    }
+                }
+                None => diff.create.push(command.clone()),
+            }
+        }
+
+        diff.delete = existing_map.into_values().cloned().collect();
+
+        diff.create.sort_by(|a, b| a.name.cmp(&b.name));
+        diff.update
+            .sort_by(|a, b| a.command.name.cmp(&b.command.name));
+        diff.delete.sort_by(|a, b| a.name.cmp(&b.name));
+        diff.unchanged.sort();
+
+        diff
+    }
+    pub fn is_empty(&self) -> bool {
+        self.create.is_empty() && self.update.is_empty() && self.delete.is_empty()
+    }
+}
+impl fmt::Display for CommandDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes ({} unchanged)", self.unchanged.len());
+        }
+        for command in &self.create {
+            writeln!(f, "+ {}", command.name)?;
+        }
+        for update in &self.update {
+            writeln!(
+                f,
+                "~ {} ({})",
+                update.command.name,
+                update.changes.join(", ")
+            )?;
+        }
+        for command in &self.delete {
+            writeln!(f, "- {}", command.name)?;
+        }
+        write!(f, "{} unchanged", self.unchanged.len())
+    }
+}
+
+pub fn command_changes(
+    current: &ApplicationCommand,
+    desired: &ApplicationCommand,
+) -> Vec<&'static str> {
+    let mut changes = Vec::new();
+
+    if current.description != desired.description {
+        changes.push("description");
+    }
+    if normalize_options(&current.options) != normalize_options(&desired.options) {
+        changes.push("options");
+    }
+    if current.default_member_permissions != desired.default_member_permissions {
+        changes.push("default_member_permissions");
+    }
+    if current.dm_permission.unwrap_or(true) != desired.dm_permission.unwrap_or(true) {
+        changes.push("dm_permission");
+    }
+    if current.nsfw.unwrap_or(false) != desired.nsfw.unwrap_or(false) {
+        changes.push("nsfw");
+    }
+    if normalize_localizations(&current.name_localizations)
+        != normalize_localizations(&desired.name_localizations)
+    {
+        changes.push("name_localizations");
+    }
+    if normalize_localizations(&current.description_localizations)
+        != normalize_localizations(&desired.description_localizations)
+    {
+        changes.push("description_localizations");
+    }
+
+    changes
+}
+
+fn normalize_localizations(
+    localizations: &Option<HashMap<String, String>>,
+) -> Option<&HashMap<String, String>> {
+    localizations.as_ref().filter(|x| !x.is_empty())
+}
+
+fn normalize_options(options: &[CommandOption]) -> Vec<CommandOption> {
+    options.iter().map(normalize_option).collect()
+}
+
+fn normalize_option(option: &CommandOption) -> CommandOption {
+    let normalize_value = |value: &Option<CommandOptionValue>| match (option.kind, value) {
+        (CommandOptionType::Number, Some(CommandOptionValue::Integer(x))) => {
+            Some(CommandOptionValue::Number(*x as f64))
+        }
+        _ => *value,
+    };
+
+    CommandOption {
+        autocomplete: Some(option.autocomplete.unwrap_or(false)),
+        channel_types: option.channel_types.clone().filter(|x| !x.is_empty()),
+        choices: option
+            .choices
+            .as_ref()
+            .filter(|x| !x.is_empty())
+            .map(|choices| {
+                choices
+                    .iter()
+                    .map(|c| CommandOptionChoice {
+                        name: c.name.clone(),
+                        name_localizations: normalize_localizations(&c.name_localizations).cloned(),
+                        value: c.value.clone(),
+                    })
+                    .collect()
+            }),
+        description: option.description.clone(),
+        description_localizations: normalize_localizations(&option.description_localizations)
+            .cloned(),
+        kind: option.kind,
+        max_length: option.max_length,
+        max_value: normalize_value(&option.max_value),
+        min_length: option.min_length,
+        min_value: normalize_value(&option.min_value),
+        name: option.name.clone(),
+        name_localizations: normalize_localizations(&option.name_localizations).cloned(),
+        options: option
+            .options
+            .as_ref()
+            .filter(|x| !x.is_empty())
+            .map(|x| normalize_options(x)),
+        required: Some(option.required.unwrap_or(false)),
+    }
+}
+
+pub(crate) async fn read_record(path: &Path) -> Vec<RegistrationTarget> {
+    let contents = match tokio::fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(source) if source.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(source) => {
+            tracing::warn!(
+                ?source,
+                "failed to read registration record {}",
+                path.display()
+            );
+            return Vec::new();
+        }
+    };
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| match line.parse() {
+            Ok(target) => Some(target),
+            Err(error) => {
+                tracing::warn!("ignoring registration record entry: {error}");
+                None
+            }
+        })
+        .collect()
+}
+
+pub(crate) async fn write_record(path: &Path, targets: &[RegistrationTarget]) {
+    let mut contents: Vec<String> = targets.iter().map(|t| t.to_string()).collect();
+    contents.sort();
+    contents.push(String::new());
+    if let Err(source) = tokio::fs::write(path, contents.join("\n")).await {
+        tracing::warn!(
+            ?source,
+            "failed to write registration record {}",
+            path.display()
+        );
+    }
+}
+
+pub(crate) async fn sync_commands(
+    http: &twilight_http::Client,
+    application_id: Id<ApplicationMarker>,
+    target: RegistrationTarget,
+    desired: &[ApplicationCommand],
+    dry_run: bool,
+) -> Result<CommandDiff, Box<dyn Error + Send + Sync>> {
+    let interaction_client = http.interaction(application_id);
+    let existing = match target {
+        RegistrationTarget::Global => interaction_client.global_commands().await?,
+        RegistrationTarget::Guild(guild_id) => interaction_client.guild_commands(guild_id).await?,
+    }
+    .models()
+    .await?;
+
+    let diff = CommandDiff::new(&existing, desired);
+    if !diff.delete.is_empty() {
+        let names: Vec<&str> = diff.delete.iter().map(|c| c.name.as_str()).collect();
+        if dry_run {
+            tracing::warn!(
+                ?target,
+                "would delete application commands: {}",
+                names.join(", ")
+            );
+        } else {
+            tracing::warn!(
+                ?target,
+                "deleting application commands: {}",
+                names.join(", ")
+            );
+        }
+    }
+    if dry_run {
+        return Ok(diff);
+    }
+
+    let application_id = application_id.get();
+    for command in &diff.create {
+        let route = match target {
+            RegistrationTarget::Global => Route::CreateGlobalCommand { application_id },
+            RegistrationTarget::Guild(guild_id) => Route::CreateGuildCommand {
+                application_id,
+                guild_id: guild_id.get(),
+            },
+        };
+        let request = Request::builder(&route).json(command)?.build();
+        http.request::<ApplicationCommand>(request).await?;
+    }
+    for update in &diff.update {
+        let command_id = update.id.get();
+        let route = match target {
+            RegistrationTarget::Global => Route::UpdateGlobalCommand {
+                application_id,
+                command_id,
+            },
+            RegistrationTarget::Guild(guild_id) => Route::UpdateGuildCommand {
+                application_id,
+                command_id,
+                guild_id: guild_id.get(),
+            },
+        };
+        let request = Request::builder(&route).json(&update.command)?.build();
+        http.request::<ApplicationCommand>(request).await?;
+    }
+    for command in &diff.delete {
+        let Some(command_id) = command.id else {
+            continue;
+        };
+        match target {
+            RegistrationTarget::Global => {
+                interaction_client.delete_global_command(command_id).await?;
+            }
+            RegistrationTarget::Guild(guild_id) => {
+                interaction_client
+                    .delete_guild_command(guild_id, command_id)
+                    .await?;
+            }
+        }
+    }
+
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use twilight_model::guild::Permissions;
+
+    fn command(name: &str, description: &str) -> ApplicationCommand {
+        ApplicationCommand {
+            application_id: None,
+            default_member_permissions: None,
+            dm_permission: None,
+            description: String::from(description),
+            description_localizations: None,
+            guild_id: None,
+            id: None,
+            kind: CommandType::ChatInput,
+            name: String::from(name),
+            name_localizations: None,
+            nsfw: None,
+            options: Vec::new(),
+            version: Id::new(1),
+        }
+    }
+
+    fn registered(id: u64, mut command: ApplicationCommand) -> ApplicationCommand {
+        command.id = Some(Id::new(id));
+        command
+    }
+
+    fn option(name: &str, kind: CommandOptionType) -> CommandOption {
+        CommandOption {
+            autocomplete: None,
+            channel_types: None,
+            choices: None,
+            description: String::from("an option"),
+            description_localizations: None,
+            kind,
+            max_length: None,
+            max_value: None,
+            min_length: None,
+            min_value: None,
+            name: String::from(name),
+            name_localizations: None,
+            options: None,
+            required: None,
+        }
+    }
+
+    #[test]
+    fn identical_commands_are_unchanged() {
+        let desired = vec![command("ping", "pong")];
+        let existing = vec![registered(1, command("ping", "pong"))];
+        let diff = CommandDiff::new(&existing, &desired);
+        assert!(diff.is_empty());
+        assert_eq!(diff.unchanged, vec![String::from("ping")]);
+    }
+
+    #[test]
+    fn detects_description_change() {
+        let existing = registered(1, command("ping", "pong"));
+        let desired = command("ping", "pong!");
+        assert_eq!(command_changes(&existing, &desired), vec!["description"]);
+    }
+
+    #[test]
+    fn detects_option_change() {
+        let existing = registered(1, command("ping", "pong"));
+        let mut desired = command("ping", "pong");
+        desired
+            .options
+            .push(option("count", CommandOptionType::Integer));
+        assert_eq!(command_changes(&existing, &desired), vec!["options"]);
+
+        let mut existing = registered(1, desired.clone());
+        existing.options[0].required = Some(true);
+        assert_eq!(command_changes(&existing, &desired), vec!["options"]);
+    }
+
+    #[test]
+    fn detects_permission_changes() {
+        let existing = registered(1, command("ban", "ban a user"));
+        let mut desired = command("ban", "ban a user");
+        desired.default_member_permissions = Some(Permissions::BAN_MEMBERS);
+        desired.dm_permission = Some(false);
+        desired.nsfw = Some(true);
+        assert_eq!(
+            command_changes(&existing, &desired),
+            vec!["default_member_permissions", "dm_permission", "nsfw"]
+        );
+    }
+
+    #[test]
+    fn default_fields_are_normalized() {
+        let mut existing = registered(1, command("ping", "pong"));
+        existing.dm_permission = Some(true);
+        existing.nsfw = Some(false);
+        existing.name_localizations = Some(HashMap::new());
+        let mut current_option = option("amount", CommandOptionType::Number);
+        current_option.autocomplete = Some(false);
+        current_option.required = Some(false);
+        current_option.choices = Some(Vec::new());
+        current_option.min_value = Some(CommandOptionValue::Number(1.0));
+        existing.options.push(current_option);
+
+        let mut desired = command("ping", "pong");
+        let mut desired_option = option("amount", CommandOptionType::Number);
+        desired_option.min_value = Some(CommandOptionValue::Integer(1));
+        desired.options.push(desired_option);
+
+        assert!(command_changes(&existing, &desired).is_empty());
+    }
+
+    #[test]
+    fn command_order_is_ignored() {
+        let existing = vec![
+            registered(1, command("b", "second")),
+            registered(2, command("a", "first")),
+        ];
+        let desired = vec![command("a", "first"), command("b", "second")];
+        let diff = CommandDiff::new(&existing, &desired);
+        assert!(diff.is_empty());
+        assert_eq!(diff.unchanged, vec![String::from("a"), String::from("b")]);
+    }
+
+    #[test]
+    fn classifies_create_update_and_delete() {
+        let existing = vec![
+            registered(1, command("keep", "same")),
+            registered(2, command("edit", "old")),
+            registered(3, command("gone", "removed")),
+        ];
+        let desired = vec![
+            command("keep", "same"),
+            command("edit", "new"),
+            command("new", "added"),
+        ];
+        let diff = CommandDiff::new(&existing, &desired);
+
+        let names = |commands: &[ApplicationCommand]| -> Vec<String> {
+            commands.iter().map(|c| c.name.clone()).collect()
+        };
+        assert_eq!(names(&diff.create), vec!["new"]);
+        assert_eq!(diff.update.len(), 1);
+        assert_eq!(diff.update[0].id, Id::new(2));
+        assert_eq!(diff.update[0].changes, vec!["description"]);
+        assert_eq!(names(&diff.delete), vec!["gone"]);
+        assert_eq!(diff.unchanged, vec![String::from("keep")]);
+    }
+
+    #[test]
+    fn same_name_with_different_kind_is_distinct() {
+        let mut user_command = command("info", "");
+        user_command.kind = CommandType::User;
+        let existing = vec![registered(1, user_command)];
+        let desired = vec![command("info", "show info")];
+        let diff = CommandDiff::new(&existing, &desired);
+        assert_eq!(diff.create.len(), 1);
+        assert_eq!(diff.delete.len(), 1);
+    }
+}
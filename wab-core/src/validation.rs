@@ -0,0 +1,583 @@
+use crate::Parameter;
+use std::collections::HashMap;
+use std::fmt;
+use twilight_model::application::command::{
+    Command as ApplicationCommand, CommandOption, CommandOptionChoiceValue, CommandOptionType,
+    CommandOptionValue, CommandType,
+};
+
+const MAX_CHAT_INPUT_COMMANDS: usize = 100;
+const MAX_CONTEXT_COMMANDS: usize = 5;
+const MAX_OPTIONS: usize = 25;
+const MAX_CHOICES: usize = 25;
+const MAX_NAME_LENGTH: usize = 32;
+const MAX_DESCRIPTION_LENGTH: usize = 100;
+const MAX_TOTAL_LENGTH: usize = 4000;
+const MAX_STRING_LENGTH: u16 = 6000;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ValidationError {
+    pub command: String,
+    pub message: String,
+}
+impl ValidationError {
+    pub fn new(command: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            message: message.into(),
+        }
+    }
+}
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}': {}", self.command, self.message)
+    }
+}
+
+pub fn validate_application_commands(commands: &[ApplicationCommand]) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    for (kind, limit) in [
+        (CommandType::ChatInput, MAX_CHAT_INPUT_COMMANDS),
+        (CommandType::User, MAX_CONTEXT_COMMANDS),
+        (CommandType::Message, MAX_CONTEXT_COMMANDS),
+    ] {
+        let count = commands.iter().filter(|c| c.kind == kind).count();
+        if count > limit {
+            errors.push(ValidationError::new(
+                "*",
+                format!("{count} {kind:?} commands exceed the limit of {limit}"),
+            ));
+        }
+    }
+
+    for command in commands {
+        let path = command.name.as_str();
+        if command.kind != CommandType::ChatInput {
+            validate_context_name(path, &command.name, &mut errors);
+            for (locale, name) in sorted(&command.name_localizations) {
+                validate_context_name(&format!("{path} [{locale}]"), name, &mut errors);
+            }
+            continue;
+        }
+
+        validate_name(path, &command.name, &mut errors);
+        validate_description(path, &command.description, &mut errors);
+        validate_localizations(
+            path,
+            &command.name_localizations,
+            &command.description_localizations,
+            &mut errors,
+        );
+        validate_options(path, &command.options, &mut errors);
+
+        let total = command.name.chars().count()
+            + command.description.chars().count()
+            + command.options.iter().map(option_length).sum::<usize>();
+        if total > MAX_TOTAL_LENGTH {
+            errors.push(ValidationError::new(
+                path,
+                format!("{total} total characters exceed the limit of {MAX_TOTAL_LENGTH}"),
+            ));
+        }
+    }
+
+    errors
+}
+
+fn validate_options(path: &str, options: &[CommandOption], errors: &mut Vec<ValidationError>) {
+    if options.len() > MAX_OPTIONS {
+        errors.push(ValidationError::new(
+            path,
+            format!(
+                "{} options exceed the limit of {MAX_OPTIONS}",
+                options.len()
+            ),
+        ));
+    }
+
+    let is_subcommand = |o: &CommandOption| {
+        matches!(
+            o.kind,
+            CommandOptionType::SubCommand | CommandOptionType::SubCommandGroup
+        )
+    };
+    if options.iter().any(is_subcommand) && !options.iter().all(is_subcommand) {
+        errors.push(ValidationError::new(
+            path,
+            "cannot mix subcommands with parameters",
+        ));
+    }
+
+    let mut seen_optional = false;
+    for option in options {
+        let option_path = format!("{path} {}", option.name);
+        validate_name(&option_path, &option.name, errors);
+        validate_description(&option_path, &option.description, errors);
+        validate_localizations(
+            &option_path,
+            &option.name_localizations,
+            &option.description_localizations,
+            errors,
+        );
+
+        if options.iter().filter(|o| o.name == option.name).count() > 1 {
+            errors.push(ValidationError::new(&option_path, "duplicate option name"));
+        }
+
+        if is_subcommand(option) {
+            validate_options(
+                &option_path,
+                option.options.as_deref().unwrap_or(&[]),
+                errors,
+            );
+            continue;
+        }
+
+        if option.required.unwrap_or(false) {
+            if seen_optional {
+                errors.push(ValidationError::new(
+                    &option_path,
+                    "required parameters must come before optional ones",
+                ));
+            }
+        } else {
+            seen_optional = true;
+        }
+
+        validate_constraints(&option_path, option, errors);
+
+        let choices = option.choices.as_deref().unwrap_or(&[]);
+        if choices.len() > MAX_CHOICES {
+            errors.push(ValidationError::new(
+                &option_path,
+                format!(
+                    "{} choices exceed the limit of {MAX_CHOICES}",
+                    choices.len()
+                ),
+            ));
+        }
+        for choice in choices {
+            validate_choice_name(&option_path, &choice.name, errors);
+            for (locale, name) in sorted(&choice.name_localizations) {
+                validate_choice_name(&format!("{option_path} [{locale}]"), name, errors);
+            }
+        }
+    }
+}
+
+pub fn validate_parameter(path: &str, parameter: &Parameter, errors: &mut Vec<ValidationError>) {
+    let lengths = [parameter.min_length(), parameter.max_length()];
+    let limit = i32::from(MAX_STRING_LENGTH);
+    if lengths
+        .into_iter()
+        .flatten()
+        .any(|x| !(0..=limit).contains(x))
+    {
+        errors.push(ValidationError::new(
+            path,
+            format!("length limits must be 0-{MAX_STRING_LENGTH}"),
+        ));
+    }
+}
+
+fn validate_constraints(path: &str, option: &CommandOption, errors: &mut Vec<ValidationError>) {
+    for length in [option.min_length, option.max_length].into_iter().flatten() {
+        if length > MAX_STRING_LENGTH {
+            errors.push(ValidationError::new(
+                path,
+                format!("length limits must be 0-{MAX_STRING_LENGTH}"),
+            ));
+        }
+    }
+    if let (Some(min), Some(max)) = (option.min_length, option.max_length) {
+        if min > max {
+            errors.push(ValidationError::new(
+                path,
+                "min_length must not exceed max_length",
+            ));
+        }
+    }
+    let value = |value: &CommandOptionValue| match value {
+        CommandOptionValue::Integer(x) => *x as f64,
+        CommandOptionValue::Number(x) => *x,
+    };
+    let min = option.min_value.as_ref().map(value);
+    let max = option.max_value.as_ref().map(value);
+    if let (Some(min), Some(max)) = (min, max) {
+        if min > max {
+            errors.push(ValidationError::new(
+                path,
+                "min_value must not exceed max_value",
+            ));
+        }
+    }
+    if option.kind != CommandOptionType::Channel
+        && option.channel_types.as_ref().is_some_and(|x| !x.is_empty())
+    {
+        errors.push(ValidationError::new(
+            path,
+            "channel_types are only allowed on channel parameters",
+        ));
+    }
+    if option.autocomplete == Some(true) && option.choices.as_ref().is_some_and(|x| !x.is_empty()) {
+        errors.push(ValidationError::new(
+            path,
+            "autocomplete cannot be combined with choices",
+        ));
+    }
+}
+
+fn validate_localizations(
+    path: &str,
+    names: &Option<HashMap<String, String>>,
+    descriptions: &Option<HashMap<String, String>>,
+    errors: &mut Vec<ValidationError>,
+) {
+    for (locale, name) in sorted(names) {
+        validate_name(&format!("{path} [{locale}]"), name, errors);
+    }
+    for (locale, description) in sorted(descriptions) {
+        validate_description(&format!("{path} [{locale}]"), description, errors);
+    }
+}
+
+fn sorted(localizations: &Option<HashMap<String, String>>) -> Vec<(&String, &String)> {
+    let mut localizations: Vec<_> = localizations.iter().flatten().collect();
+    localizations.sort();
+    localizations
+}
+
+fn validate_context_name(path: &str, name: &str, errors: &mut Vec<ValidationError>) {
+    let length = name.chars().count();
+    if length == 0 || length > MAX_NAME_LENGTH {
+        errors.push(ValidationError::new(
+            path,
+            format!("name '{name}' must be 1-{MAX_NAME_LENGTH} characters"),
+        ));
+    }
+}
+
+fn validate_choice_name(path: &str, name: &str, errors: &mut Vec<ValidationError>) {
+    let length = name.chars().count();
+    if length == 0 || length > MAX_DESCRIPTION_LENGTH {
+        errors.push(ValidationError::new(
+            path,
+            format!("choice '{name}' must be 1-{MAX_DESCRIPTION_LENGTH} characters"),
+        ));
+    }
+}
+
+fn validate_name(path: &str, name: &str, errors: &mut Vec<ValidationError>) {
+    let length = name.chars().count();
+    if length == 0 || length > MAX_NAME_LENGTH {
+        errors.push(ValidationError::new(
+            path,
+            format!("name '{name}' must be 1-{MAX_NAME_LENGTH} characters"),
+        ));
+    }
+    if !name.chars().all(is_name_char) {
+        errors.push(ValidationError::new(
+            path,
+            format!("name '{name}' may only contain letters, numbers, '-' and '_'"),
+        ));
+    }
+    if name
+        .chars()
+        .any(|c| c.to_lowercase().ne(std::iter::once(c)))
+    {
+        errors.push(ValidationError::new(
+            path,
+            format!("name '{name}' must be lowercase"),
+        ));
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c == '-' || c == '_' || c.is_alphanumeric()
+}
+
+fn validate_description(path: &str, description: &str, errors: &mut Vec<ValidationError>) {
+    let length = description.chars().count();
+    if length == 0 || length > MAX_DESCRIPTION_LENGTH {
+        errors.push(ValidationError::new(
+            path,
+            format!("description must be 1-{MAX_DESCRIPTION_LENGTH} characters"),
+        ));
+    }
+}
+
+fn option_length(option: &CommandOption) -> usize {
+    let choices: usize = option
+        .choices
+        .iter()
+        .flatten()
+        .map(|choice| {
+            let value = match &choice.value {
+                CommandOptionChoiceValue::String(x) => x.chars().count(),
+                CommandOptionChoiceValue::Integer(x) => x.to_string().len(),
+                CommandOptionChoiceValue::Number(x) => x.to_string().len(),
+            };
+            choice.name.chars().count() + value
+        })
+        .sum();
+    let options: usize = option.options.iter().flatten().map(option_length).sum();
+    option.name.chars().count() + option.description.chars().count() + choices + options
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use twilight_model::application::command::CommandOptionChoice;
+    use twilight_model::id::Id;
+
+    fn command(name: &str, description: &str) -> ApplicationCommand {
+        ApplicationCommand {
+            application_id: None,
+            default_member_permissions: None,
+            dm_permission: None,
+            description: String::from(description),
+            description_localizations: None,
+            guild_id: None,
+            id: None,
+            kind: CommandType::ChatInput,
+            name: String::from(name),
+            name_localizations: None,
+            nsfw: None,
+            options: Vec::new(),
+            version: Id::new(1),
+        }
+    }
+
+    fn option(name: &str, kind: CommandOptionType, required: bool) -> CommandOption {
+        CommandOption {
+            autocomplete: None,
+            channel_types: None,
+            choices: None,
+            description: String::from("an option"),
+            description_localizations: None,
+            kind,
+            max_length: None,
+            max_value: None,
+            min_length: None,
+            min_value: None,
+            name: String::from(name),
+            name_localizations: None,
+            options: None,
+            required: Some(required),
+        }
+    }
+
+    fn messages(errors: Vec<ValidationError>) -> Vec<String> {
+        errors.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn valid_command_has_no_errors() {
+        let mut cmd = command("ping", "check latency");
+        cmd.options
+            .push(option("count", CommandOptionType::Integer, true));
+        cmd.options
+            .push(option("verbose", CommandOptionType::Boolean, false));
+        assert!(validate_application_commands(&[cmd]).is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_names() {
+        let errors = messages(validate_application_commands(&[
+            command("Ping", "uppercase"),
+            command("has space", "space"),
+            command(&"x".repeat(33), "too long"),
+        ]));
+        assert_eq!(
+            errors,
+            vec![
+                "'Ping': name 'Ping' must be lowercase",
+                "'has space': name 'has space' may only contain letters, numbers, '-' and '_'",
+                format!("'{0}': name '{0}' must be 1-32 characters", "x".repeat(33)).as_str(),
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_characters_not_bytes() {
+        let name = "ä".repeat(32);
+        let description = "ö".repeat(100);
+        assert!(validate_application_commands(&[command(&name, &description)]).is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_descriptions() {
+        let errors = messages(validate_application_commands(&[
+            command("empty", ""),
+            command("long", &"x".repeat(101)),
+        ]));
+        assert_eq!(
+            errors,
+            vec![
+                "'empty': description must be 1-100 characters",
+                "'long': description must be 1-100 characters",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_option_problems() {
+        let mut cmd = command("cmd", "a command");
+        cmd.options
+            .push(option("a", CommandOptionType::String, false));
+        cmd.options
+            .push(option("b", CommandOptionType::String, true));
+        cmd.options
+            .push(option("a", CommandOptionType::String, false));
+        let errors = messages(validate_application_commands(&[cmd]));
+        assert_eq!(
+            errors,
+            vec![
+                "'cmd a': duplicate option name",
+                "'cmd b': required parameters must come before optional ones",
+                "'cmd a': duplicate option name",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_mixed_subcommands_and_too_many_options() {
+        let mut cmd = command("cmd", "a command");
+        cmd.options
+            .push(option("sub", CommandOptionType::SubCommand, false));
+        cmd.options
+            .push(option("value", CommandOptionType::String, false));
+        let errors = messages(validate_application_commands(&[cmd]));
+        assert_eq!(
+            errors,
+            vec!["'cmd': cannot mix subcommands with parameters"]
+        );
+
+        let mut cmd = command("cmd", "a command");
+        for i in 0..26 {
+            cmd.options
+                .push(option(&format!("o{i}"), CommandOptionType::String, false));
+        }
+        let errors = messages(validate_application_commands(&[cmd]));
+        assert_eq!(errors, vec!["'cmd': 26 options exceed the limit of 25"]);
+    }
+
+    #[test]
+    fn rejects_invalid_choices() {
+        let mut cmd = command("cmd", "a command");
+        let mut fruit = option("fruit", CommandOptionType::String, false);
+        fruit.choices = Some(vec![CommandOptionChoice {
+            name: String::new(),
+            name_localizations: Some(HashMap::from([(String::from("de"), "x".repeat(101))])),
+            value: CommandOptionChoiceValue::String(String::from("apple")),
+        }]);
+        cmd.options.push(fruit);
+        let errors = messages(validate_application_commands(&[cmd]));
+        assert_eq!(
+            errors,
+            vec![
+                "'cmd fruit': choice '' must be 1-100 characters",
+                format!(
+                    "'cmd fruit [de]': choice '{}' must be 1-100 characters",
+                    "x".repeat(101)
+                )
+                .as_str(),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_localizations() {
+        let mut cmd = command("cmd", "a command");
+        cmd.name_localizations = Some(HashMap::from([(
+            String::from("de"),
+            String::from("Befehl"),
+        )]));
+        cmd.description_localizations = Some(HashMap::from([(String::from("fr"), String::new())]));
+        let mut value = option("value", CommandOptionType::String, false);
+        value.name_localizations = Some(HashMap::from([(
+            String::from("de"),
+            String::from("ein wert"),
+        )]));
+        cmd.options.push(value);
+        let errors = messages(validate_application_commands(&[cmd]));
+        assert_eq!(
+            errors,
+            vec![
+                "'cmd [de]': name 'Befehl' must be lowercase",
+                "'cmd [fr]': description must be 1-100 characters",
+                "'cmd value [de]': name 'ein wert' may only contain letters, numbers, '-' and '_'",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_constraints() {
+        let mut cmd = command("cmd", "a command");
+        let mut text = option("text", CommandOptionType::String, false);
+        text.min_length = Some(10);
+        text.max_length = Some(6001);
+        let mut amount = option("amount", CommandOptionType::Integer, false);
+        amount.min_value = Some(CommandOptionValue::Integer(5));
+        amount.max_value = Some(CommandOptionValue::Integer(1));
+        amount.channel_types = Some(vec![twilight_model::channel::ChannelType::GuildText]);
+        let mut fruit = option("fruit", CommandOptionType::String, false);
+        fruit.autocomplete = Some(true);
+        fruit.choices = Some(vec![CommandOptionChoice {
+            name: String::from("apple"),
+            name_localizations: None,
+            value: CommandOptionChoiceValue::String(String::from("apple")),
+        }]);
+        cmd.options.extend([text, amount, fruit]);
+        let errors = messages(validate_application_commands(&[cmd]));
+        assert_eq!(
+            errors,
+            vec![
+                "'cmd text': length limits must be 0-6000",
+                "'cmd amount': min_value must not exceed max_value",
+                "'cmd amount': channel_types are only allowed on channel parameters",
+                "'cmd fruit': autocomplete cannot be combined with choices",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_context_command_problems() {
+        let mut commands = Vec::new();
+        for i in 0..6 {
+            let mut cmd = command(&format!("User {i}"), "");
+            cmd.kind = CommandType::User;
+            commands.push(cmd);
+        }
+        commands[0].name = "x".repeat(33);
+        let errors = messages(validate_application_commands(&commands));
+        assert_eq!(
+            errors,
+            vec![
+                String::from("'*': 6 User commands exceed the limit of 5"),
+                format!("'{0}': name '{0}' must be 1-32 characters", "x".repeat(33)),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_oversized_commands() {
+        let mut cmd = command("cmd", &"d".repeat(100));
+        for i in 0..25 {
+            let mut o = option(&format!("o{i:0>31}"), CommandOptionType::String, false);
+            o.description = "d".repeat(100);
+            o.choices = Some(vec![CommandOptionChoice {
+                name: "c".repeat(30),
+                name_localizations: None,
+                value: CommandOptionChoiceValue::String(String::from("v")),
+            }]);
+            cmd.options.push(o);
+        }
+        let errors = messages(validate_application_commands(&[cmd]));
+        assert_eq!(
+            errors,
+            vec!["'cmd': 4178 total characters exceed the limit of 4000"]
+        );
+    }
+}
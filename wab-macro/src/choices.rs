@@ -0,0 +1,184 @@
+use darling::{ast::Data, util::Ignored, Error, FromDeriveInput, FromVariant};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{DeriveInput, Ident, Lit};
+
+const MAX_CHOICES: usize = 25;
+const MAX_NAME_LENGTH: usize = 100;
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(supports(enum_unit))]
+struct ChoicesArgs {
+    ident: Ident,
+    data: Data<ChoiceArgs, Ignored>,
+}
+
+#[derive(Debug, FromVariant)]
+#[darling(attributes(choice))]
+struct ChoiceArgs {
+    ident: Ident,
+    name: Option<String>,
+    value: Option<Lit>,
+}
+
+pub fn derive(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let args = ChoicesArgs::from_derive_input(input)?;
+    let name = &args.ident;
+    let variants = args.data.take_enum().unwrap();
+
+    if variants.is_empty() || variants.len() > MAX_CHOICES {
+        return Err(
+            Error::custom(format!("expected between 1 and {MAX_CHOICES} variants")).with_span(name),
+        );
+    }
+
+    let integer = variants
+        .iter()
+        .any(|v| matches!(v.value, Some(Lit::Int(_))));
+
+    let mut errors = Error::accumulator();
+    let mut choices = Vec::new();
+    let mut arms = Vec::new();
+    let mut values = Vec::new();
+    let mut names = Vec::new();
+
+    for variant in variants {
+        let ident = &variant.ident;
+        let choice_name = variant.name.unwrap_or_else(|| ident.to_string());
+        let length = choice_name.chars().count();
+        if length == 0 || length > MAX_NAME_LENGTH {
+            errors.push(
+                Error::custom(format!(
+                    "choice names must be 1-{MAX_NAME_LENGTH} characters"
+                ))
+                .with_span(ident),
+            );
+        }
+
+        let (value, pattern) = match (&variant.value, integer) {
+            (Some(Lit::Int(x)), true) => match x.base10_parse::<i64>() {
+                Ok(v) => (
+                    quote! {wab::ParameterChoiceType::Integer(#v)},
+                    v.to_string(),
+                ),
+                Err(e) => {
+                    errors.push(Error::from(e));
+                    continue;
+                }
+            },
+            (Some(Lit::Str(x)), false) => (
+                quote! {wab::ParameterChoiceType::String(String::from(#x))},
+                x.value(),
+            ),
+            (None, false) => {
+                let x = ident.to_string();
+                (
+                    quote! {wab::ParameterChoiceType::String(String::from(#x))},
+                    x,
+                )
+            }
+            (None, true) => {
+                errors.push(
+                    Error::custom("expected an integer `value`, as other variants have one")
+                        .with_span(ident),
+                );
+                continue;
+            }
+            (Some(lit), _) => {
+                errors.push(
+                    Error::custom(
+                        "expected a string or integer `value` matching the other variants",
+                    )
+                    .with_span(lit),
+                );
+                continue;
+            }
+        };
+        let length = pattern.chars().count();
+        if !integer && (length == 0 || length > MAX_NAME_LENGTH) {
+            let span = variant.value.as_ref().map_or(ident.span(), Lit::span);
+            errors.push(
+                Error::custom(format!(
+                    "choice values must be 1-{MAX_NAME_LENGTH} characters"
+                ))
+                .with_span(&span),
+            );
+        }
+        if values.contains(&pattern) {
+            errors.push(
+                Error::custom(format!("duplicate choice value '{pattern}'")).with_span(ident),
+            );
+        }
+
+        choices.push(quote! {wab::ParameterChoice::new(#choice_name, #value)});
+        names.push(quote! {Self::#ident => #choice_name});
+        arms.push(if integer {
+            let pattern: i64 = pattern.parse().unwrap();
+            quote! {#pattern => Ok(Self::#ident)}
+        } else {
+            quote! {#pattern => Ok(Self::#ident)}
+        });
+        values.push(pattern);
+    }
+    errors.finish()?;
+
+    let (kind, conversion) = if integer {
+        (
+            quote! {wab::ParameterType::Integer},
+            quote! {
+                wab::Argument::Integer(x) => match x {
+                    #(#arms,)*
+                    _ => Err(format!("unknown choice '{x}'")),
+                }
+            },
+        )
+    } else {
+        (
+            quote! {wab::ParameterType::String},
+            quote! {
+                wab::Argument::String(x) => match x.as_str() {
+                    #(#arms,)*
+                    _ => Err(format!("unknown choice '{x}'")),
+                }
+            },
+        )
+    };
+
+    let from_text = integer.then(|| {
+        quote! {
+            fn from_text(text: &str) -> Result<Self, String> {
+                match text.parse::<i64>() {
+                    Ok(x) => Self::from_argument(wab::Argument::Integer(x)),
+                    Err(_) => Err(format!("unknown choice '{text}'")),
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl wab::Choices for #name {
+            fn choices() -> Vec<wab::ParameterChoice> {
+                vec![#(#choices),*]
+            }
+        }
+        impl wab::FromArgument for #name {
+            const KIND: wab::ParameterType = #kind;
+            fn constrain(parameter: &mut wab::ParameterBuilder) -> &mut wab::ParameterBuilder {
+                parameter.choices(<Self as wab::Choices>::choices())
+            }
+            fn from_argument(argument: wab::Argument) -> Result<Self, String> {
+                match argument {
+                    #conversion,
+                    _ => Err(String::from("argument type mismatched")),
+                }
+            }
+            #from_text
+            fn describe(&self) -> Option<String> {
+                let name = match self {
+                    #(#names,)*
+                };
+                Some(String::from(name))
+            }
+        }
+    })
+}
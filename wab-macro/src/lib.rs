@@ -1,37 +1,232 @@
-use darling::{ast::NestedMeta, Error, FromMeta};
+use darling::{ast::NestedMeta, util::SpannedValue, Error, FromMeta};
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
-use quote::{quote, ToTokens};
+use quote::{quote, quote_spanned, ToTokens};
 use syn::{parse_macro_input, Ident, Type};
 
+mod choices;
+mod modal;
 mod parse;
+mod validate;
 use parse::{FunctionParse, StructParse};
 
 #[derive(Debug, FromMeta)]
 struct CommandMacroArgs {
-    name: String,
-    description: String,
+    name: SpannedValue<String>,
+    description: Option<SpannedValue<String>>,
     #[darling(default, multiple)]
     parameter: Vec<ParameterMacroArgs>,
+    #[darling(flatten)]
+    settings: CommandSettingsArgs,
+}
+
+#[derive(Debug, FromMeta)]
+struct ContextCommandMacroArgs {
+    name: SpannedValue<String>,
+    #[darling(flatten)]
+    settings: CommandSettingsArgs,
+}
+
+#[derive(Debug, FromMeta)]
+struct CommandSettingsArgs {
+    guilds: Option<GuildList>,
+    #[darling(default)]
+    global: bool,
+    #[darling(default)]
+    checks: IdentList,
+    cooldown: Option<CooldownMacroArgs>,
+    permissions: Option<String>,
+    dm: Option<bool>,
+    nsfw: Option<bool>,
+}
+impl CommandSettingsArgs {
+    fn quote_builder_calls(self, fn_name: &Ident) -> Result<TokenStream2, Error> {
+        let scope = match (self.guilds, self.global) {
+            (Some(guilds), false) => {
+                let scope = guilds.to_scope();
+                Some(quote! {.scope(#scope)})
+            }
+            (None, true) => Some(quote! {.scope(wab::CommandScope::Global)}),
+            (None, false) => None,
+            (Some(_), true) => {
+                return Err(
+                    Error::custom("`guilds` and `global` cannot both be set").with_span(fn_name)
+                );
+            }
+        };
+
+        let checks = quote_checks(&self.checks);
+
+        let cooldown = match self.cooldown {
+            Some(CooldownMacroArgs { per, rate, seconds }) => {
+                let bucket = match per.as_str() {
+                    "user" => quote! {wab::CooldownBucket::User},
+                    "member" => quote! {wab::CooldownBucket::Member},
+                    "channel" => quote! {wab::CooldownBucket::Channel},
+                    "guild" => quote! {wab::CooldownBucket::Guild},
+                    "global" => quote! {wab::CooldownBucket::Global},
+                    _ => return Err(Error::unknown_value(&per).with_span(fn_name)),
+                };
+                if rate == 0 {
+                    return Err(
+                        Error::custom("cooldown `rate` must be at least 1").with_span(fn_name)
+                    );
+                }
+                Some(quote! {
+                    .cooldown(wab::Cooldown::new(
+                        #bucket,
+                        #rate,
+                        std::time::Duration::from_secs(#seconds),
+                    ))
+                })
+            }
+            None => None,
+        };
+
+        let permissions = self
+            .permissions
+            .as_deref()
+            .map(quote_permissions)
+            .transpose()
+            .map_err(|e| e.with_span(fn_name))?
+            .map(|permissions| quote! {.default_member_permissions(#permissions)});
+        let dm = self.dm.map(|dm| quote! {.dm_permission(#dm)});
+        let nsfw = self.nsfw.map(|nsfw| quote! {.nsfw(#nsfw)});
+
+        Ok(quote! {
+            #scope
+            #(.check(#checks))*
+            #cooldown
+            #permissions
+            #dm
+            #nsfw
+        })
+    }
+}
+
+#[derive(Debug, FromMeta)]
+struct CooldownMacroArgs {
+    per: String,
+    #[darling(default = "default_cooldown_rate")]
+    rate: u32,
+    seconds: u64,
+}
+
+fn default_cooldown_rate() -> u32 {
+    1
 }
 
 #[derive(Debug, FromMeta)]
 struct ParameterMacroArgs {
-    name: String,
-    description: String,
+    name: Option<SpannedValue<String>>,
+    description: Option<SpannedValue<String>>,
     #[darling(default, multiple)]
     choice: Vec<ChoiceMacroArgs>,
+    min: Option<syn::Lit>,
+    max: Option<syn::Lit>,
     min_value_int: Option<i64>,
     max_value_int: Option<i64>,
     min_value_number: Option<f64>,
     max_value_number: Option<f64>,
     min_length: Option<i32>,
     max_length: Option<i32>,
+    #[darling(default)]
+    channel_types: IdentList,
+    autocomplete: Option<Ident>,
+    default: Option<DefaultValue>,
+    #[darling(default)]
+    rest: bool,
+    delimiter: Option<String>,
+    // `min`/`max` already bound the values of each element, so list sizes get their own names
+    min_count: Option<usize>,
+    max_count: Option<usize>,
+}
+
+impl ParameterMacroArgs {
+    fn min_value(&self) -> Option<i64> {
+        int_bound(self.min.as_ref())
+    }
+    fn max_value(&self) -> Option<i64> {
+        int_bound(self.max.as_ref())
+    }
+}
+
+fn int_bound(lit: Option<&syn::Lit>) -> Option<i64> {
+    match lit {
+        Some(syn::Lit::Int(x)) => x.base10_parse().ok(),
+        _ => None,
+    }
+}
+
+#[derive(Debug)]
+struct DefaultValue {
+    expr: syn::Expr,
+}
+impl DefaultValue {
+    fn literal(&self) -> Option<DefaultLiteral> {
+        let (negative, lit) = match &self.expr {
+            syn::Expr::Lit(x) => (false, &x.lit),
+            syn::Expr::Unary(syn::ExprUnary {
+                op: syn::UnOp::Neg(_),
+                expr,
+                ..
+            }) => match expr.as_ref() {
+                syn::Expr::Lit(x) => (true, &x.lit),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let sign = if negative { -1 } else { 1 };
+        match lit {
+            syn::Lit::Int(x) => x
+                .base10_parse::<i64>()
+                .ok()
+                .map(|x| DefaultLiteral::Int(sign * x)),
+            syn::Lit::Float(x) => x
+                .base10_parse::<f64>()
+                .ok()
+                .map(|x| DefaultLiteral::Float(sign as f64 * x)),
+            syn::Lit::Str(x) if !negative => Some(DefaultLiteral::Str(x.value())),
+            syn::Lit::Bool(x) if !negative => Some(DefaultLiteral::Bool(x.value)),
+            _ => None,
+        }
+    }
+    fn help(&self) -> String {
+        match (self.literal(), &self.expr) {
+            (Some(DefaultLiteral::Int(x)), _) => x.to_string(),
+            (Some(DefaultLiteral::Float(x)), _) => x.to_string(),
+            (Some(DefaultLiteral::Str(x)), _) => x,
+            (Some(DefaultLiteral::Bool(x)), _) => x.to_string(),
+            (None, syn::Expr::Path(x)) => match x.path.segments.last() {
+                Some(segment) => segment.ident.to_string(),
+                None => x.to_token_stream().to_string(),
+            },
+            (None, x) => x.to_token_stream().to_string(),
+        }
+    }
+}
+impl FromMeta for DefaultValue {
+    fn from_meta(item: &syn::Meta) -> Result<Self, Error> {
+        let expr = match item {
+            syn::Meta::NameValue(x) => x.value.clone(),
+            syn::Meta::List(x) => syn::parse2(x.tokens.clone())?,
+            syn::Meta::Path(_) => return Err(Error::unsupported_format("word")),
+        };
+        Ok(Self { expr })
+    }
+}
+
+#[derive(Debug)]
+enum DefaultLiteral {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
 }
 
 #[derive(Debug, FromMeta)]
 struct ChoiceMacroArgs {
-    name: String,
+    name: SpannedValue<String>,
     value_string: Option<String>,
     value_int: Option<i64>,
     value_number: Option<f64>,
@@ -52,7 +247,7 @@ pub fn command(attr: TokenStream, input: TokenStream) -> TokenStream {
         }
     };
     let FunctionParse {
-        attributes: _,
+        attributes,
         visibility,
         name: fn_name,
         fn_parameters,
@@ -60,130 +255,312 @@ pub fn command(attr: TokenStream, input: TokenStream) -> TokenStream {
         body,
     } = parse_macro_input!(input as FunctionParse);
 
-    assert!(attr_args.parameter.len() == fn_parameters.len() - 1);
+    let mut errors = Error::accumulator();
+    validate::validate_command_name(&attr_args.name, &mut errors);
+    let command_description = attr_args
+        .description
+        .or_else(|| doc_comment(&attributes).map(|doc| SpannedValue::new(doc, fn_name.span())));
+    match &command_description {
+        Some(description) => validate::validate_description(description, &mut errors),
+        None => errors
+            .push(Error::custom("expected `description` or a doc comment").with_span(&fn_name)),
+    }
+
+    let Some((_, data_parameters)) = fn_parameters.split_first() else {
+        errors.push(Error::custom("expected a CommandContext parameter").with_span(&fn_name));
+        return TokenStream::from(errors.finish().unwrap_err().write_errors());
+    };
+
+    let parameter_macro_args: Vec<ParameterMacroArgs> = if attr_args.parameter.is_empty() {
+        data_parameters
+            .iter()
+            .filter_map(|fn_parameter| errors.handle(argument_macro_args(&fn_parameter.attributes)))
+            .collect()
+    } else {
+        for extra in attr_args.parameter.iter().skip(data_parameters.len()) {
+            errors.push(
+                Error::custom("`parameter` has no matching function argument")
+                    .with_span(&extra.name.as_ref().map_or(fn_name.span(), |x| x.span())),
+            );
+        }
+        for extra in data_parameters.iter().skip(attr_args.parameter.len()) {
+            errors.push(
+                Error::custom("function argument has no matching `parameter`")
+                    .with_span(&extra.name),
+            );
+        }
+        for fn_parameter in data_parameters.iter() {
+            let mut attributes = fn_parameter.attributes.iter();
+            if let Some(attr) = attributes.find(|attr| !attr.path().is_ident("doc")) {
+                errors.push(
+                    Error::custom("argument attributes cannot be combined with `parameter(...)`")
+                        .with_span(attr),
+                );
+            }
+        }
+        attr_args.parameter
+    };
 
     let mut parameters = Vec::new();
     let mut fn_parameter_names = Vec::new();
     let mut arg_conversions = Vec::new();
+    let mut kind_assertions = Vec::new();
 
-    for (fn_parameter, parameter_macro_args) in
-        (&fn_parameters[1..]).iter().zip(attr_args.parameter)
+    for (fn_parameter, mut parameter_macro_args) in data_parameters.iter().zip(parameter_macro_args)
     {
-        let required;
-        let (arg_type, parameter_type) = match &fn_parameter.kind {
-            Type::Path(ty) => {
-                let ident_str = ty.to_token_stream().to_string();
-                required = !ident_str.starts_with("Option");
-                match ident_str.as_str() {
-                    "String" | "Option < String >" => (
-                        quote! {wab::Argument::String},
-                        quote! {wab::ParameterType::String},
-                    ),
-                    "i64" | "Option < i64 >" => (
-                        quote! {wab::Argument::Integer},
-                        quote! {wab::ParameterType::Integer},
-                    ),
-                    "f64" | "Option < f64 >" => (
-                        quote! {wab::Argument::Float},
-                        quote! {wab::ParameterType::Float},
-                    ),
-                    "bool" | "Option < bool >" => (
-                        quote! {wab::Argument::Boolean},
-                        quote! {wab::ParameterType::Boolean},
-                    ),
-                    _ => panic!("invalid parameter type"),
-                }
-            }
-            _ => panic!("invalid parameter type"),
+        let ty = &fn_parameter.kind;
+        let list = vec_inner(ty);
+        let element = list.unwrap_or(ty);
+        let rest = parameter_macro_args.rest;
+        let parameter_ty = if rest {
+            quote! {String}
+        } else {
+            element.to_token_stream()
         };
 
+        let arg_name = parameter_macro_args.name.take().unwrap_or_else(|| {
+            SpannedValue::new(fn_parameter.name.to_string(), fn_parameter.name.span())
+        });
+        let description = parameter_macro_args.description.take().or_else(|| {
+            doc_comment(&fn_parameter.attributes)
+                .map(|doc| SpannedValue::new(doc, fn_parameter.name.span()))
+        });
+        if description.is_none() {
+            errors.push(
+                Error::custom("expected `description` or a doc comment")
+                    .with_span(&fn_parameter.name),
+            );
+        }
+
+        let counts = (
+            parameter_macro_args.min_count.take().unwrap_or(1),
+            parameter_macro_args.max_count.take(),
+        );
+        if list.is_some() {
+            validate::validate_list(&arg_name, counts, rest, &mut errors);
+        } else if rest || parameter_macro_args.delimiter.is_some() || counts != (1, None) {
+            errors.push(
+                Error::custom(
+                    "`rest`, `delimiter`, `min_count` and `max_count` require a `Vec` argument",
+                )
+                .with_span(ty),
+            );
+        }
+        if parameter_macro_args.delimiter.is_some() && !rest {
+            errors.push(Error::custom("`delimiter` requires `rest`").with_span(&arg_name.span()));
+        }
+        if let Err(e) = resolve_bounds(&mut parameter_macro_args) {
+            errors.push(e.with_span(&arg_name.span()));
+        }
+
+        validate::validate_parameter(
+            &arg_name,
+            description.as_ref(),
+            &parameter_macro_args,
+            &mut errors,
+        );
+        kind_assertions.extend(quote_kind_assertions(
+            &parameter_ty,
+            &parameter_macro_args,
+            arg_name.span(),
+        ));
+        if let Some(default) = &parameter_macro_args.default {
+            if is_option(ty) || list.is_some() {
+                errors.push(
+                    Error::custom("`default` requires a non-`Option`, non-`Vec` argument")
+                        .with_span(ty),
+                );
+            }
+            validate::validate_default(
+                &arg_name,
+                description.as_ref(),
+                default,
+                &parameter_macro_args,
+                &mut errors,
+            );
+        }
+
         let ParameterMacroArgs {
-            name: arg_name,
-            description,
+            name: _,
+            description: _,
             choice,
+            min,
+            max,
             min_value_int,
             max_value_int,
             min_value_number,
             max_value_number,
             min_length,
             max_length,
+            channel_types,
+            autocomplete,
+            default,
+            rest: _,
+            delimiter,
+            min_count: _,
+            max_count: _,
         } = parameter_macro_args;
+        let arg_name = arg_name.as_str();
+        let description = description.as_deref().map_or("", String::as_str);
 
         let choices: Vec<TokenStream2> = choice
             .into_iter()
-            .map(|x| {
-                let name = x.name;
+            .filter_map(|x| {
+                let name = x.name.as_str();
                 if let Some(value) = x.value_int {
-                    quote! {wab::ParameterChoice::new(#name, wab::ParameterChoiceType::Integer(#value))}
+                    Some(quote! {wab::ParameterChoice::new(#name, wab::ParameterChoiceType::Integer(#value))})
                 } else if let Some(value) = x.value_number {
-                    quote! {wab::ParameterChoice::new(#name, wab::ParameterChoiceType::Float(#value))}
-                } else if let Some(value) = x.value_string {
-                    quote! {wab::ParameterChoice::new(#name, wab::ParameterChoiceType::String(String::from(#value)))}
+                    Some(quote! {wab::ParameterChoice::new(#name, wab::ParameterChoiceType::Float(#value))})
                 } else {
-                    panic!("Expected value in parameter choice");
+                    x.value_string.map(|value| {
+                        quote! {wab::ParameterChoice::new(#name, wab::ParameterChoiceType::String(String::from(#value)))}
+                    })
                 }
             })
             .collect();
 
-        let min_value_int = quote_option(&min_value_int);
-        let max_value_int = quote_option(&max_value_int);
-        let min_value_number = quote_option(&min_value_number);
-        let max_value_number = quote_option(&max_value_number);
-        let min_length = quote_option(&min_length);
-        let max_length = quote_option(&max_length);
-
-        parameters.push(quote! {
-            wab::Parameter::builder()
-                .name(#arg_name)
-                .description(#description)
-                .kind(#parameter_type)
-                .required(#required)
-                #(.choice(#choices))*
-                .min_value_int(#min_value_int)
-                .max_value_int(#max_value_int)
-                .min_value_number(#min_value_number)
-                .max_value_number(#max_value_number)
-                .min_length(#min_length)
-                .max_length(#max_length)
-                .build()
+        let min_value = int_bound(min.as_ref()).map(|x| quote_bound(&parameter_ty, x, true));
+        let max_value = int_bound(max.as_ref()).map(|x| quote_bound(&parameter_ty, x, false));
+        let min_value_int = min_value_int.map(|x| quote! {.min_value_int(Some(#x))});
+        let max_value_int = max_value_int.map(|x| quote! {.max_value_int(Some(#x))});
+        let min_value_number = min_value_number.map(|x| quote! {.min_value_number(Some(#x))});
+        let max_value_number = max_value_number.map(|x| quote! {.max_value_number(Some(#x))});
+        let min_length = min_length.map(|x| quote! {.min_length(Some(#x))});
+        let max_length = max_length.map(|x| quote! {.max_length(Some(#x))});
+        let channel_types = channel_types.idents;
+        let autocomplete = autocomplete.map(|x| quote! {.autocomplete(Some(#x))});
+        let default_value = default.as_ref().map(|x| {
+            let help = x.help();
+            let expr = &x.expr;
+            quote! {
+                .default_value(Some(
+                    wab::FromArgument::describe(&{ let v: #ty = #expr; v })
+                        .unwrap_or_else(|| String::from(#help)),
+                ))
+            }
         });
-        fn_parameter_names.push(&fn_parameter.name);
 
+        let quote_parameter = |name: &str, required: TokenStream2| {
+            quote! {
+                <#parameter_ty as wab::FromArgument>::constrain(
+                    wab::Parameter::builder()
+                        .name(#name)
+                        .description(#description)
+                        .kind(<#parameter_ty as wab::FromArgument>::KIND)
+                        .required(#required)
+                )
+                    #(.choice(#choices))*
+                    #min_value
+                    #max_value
+                    #min_value_int
+                    #max_value_int
+                    #min_value_number
+                    #max_value_number
+                    #min_length
+                    #max_length
+                    #(.channel_type(wab::twilight_model::channel::ChannelType::#channel_types))*
+                    #autocomplete
+                    #default_value
+                    .build()
+            }
+        };
+        fn_parameter_names.push(&fn_parameter.name);
         let var_name = &fn_parameter.name;
-        let arg_conversion = if required {
+        let (min, max) = counts;
+        let max_count = quote_option(&max);
+
+        let arg_conversion = if rest {
+            let required = min > 0;
+            parameters.push(quote_parameter(arg_name, quote! {#required}));
+            let delimiter = quote_option(&delimiter);
             quote! {
-                let mut #var_name = match args.remove(#arg_name) {
-                    Some(#arg_type(x)) => x,
-                    None | _ => panic!("argument type mismatched")
+                let converted = match args.remove(#arg_name) {
+                    Some(wab::Argument::String(x)) => {
+                        wab::argument::from_delimited::<#element>(&x, #delimiter)
+                    }
+                    Some(_) => Err(String::from("argument type mismatched")),
+                    None => Ok(Vec::new()),
+                };
+                let mut #var_name: #ty = match converted
+                    .and_then(|x| wab::argument::check_count(x.len(), #min, #max_count).map(|_| x))
+                {
+                    Ok(x) => x,
+                    Err(reason) => return wab::error::argument_error(#arg_name, &reason),
                 };
             }
+        } else if list.is_some() {
+            let names = list_names(arg_name, max.unwrap_or(1));
+            for (i, name) in names.iter().enumerate() {
+                let required = i < min;
+                parameters.push(quote_parameter(name, quote! {#required}));
+            }
+            quote! {
+                let mut #var_name: #ty = Vec::new();
+                for name in [#(#names),*] {
+                    if let Some(x) = args.remove(name) {
+                        match <#element as wab::FromArgument>::from_argument(x) {
+                            Ok(x) => #var_name.push(x),
+                            Err(reason) => return wab::error::argument_error(name, &reason),
+                        }
+                    }
+                }
+                if let Err(reason) = wab::argument::check_count(#var_name.len(), #min, #max_count) {
+                    return wab::error::argument_error(#arg_name, &reason);
+                }
+            }
         } else {
+            let required = match &default {
+                Some(_) => quote! {false},
+                None => quote! {<#ty as wab::FromArgument>::required()},
+            };
+            parameters.push(quote_parameter(arg_name, required));
+            let missing = match &default {
+                Some(x) => {
+                    let expr = &x.expr;
+                    quote! {Ok({ let v: #ty = #expr; v })}
+                }
+                None => quote! {<#ty as wab::FromArgument>::from_missing()},
+            };
             quote! {
-                let mut #var_name = match args.remove(#arg_name) {
-                    Some(#arg_type(x)) => Some(x),
-                    None => None,
-                    _ => panic!("argument type mismatched")
+                let converted = match args.remove(#arg_name) {
+                    Some(x) => <#ty as wab::FromArgument>::from_argument(x),
+                    None => #missing,
+                };
+                let mut #var_name: #ty = match converted {
+                    Ok(x) => x,
+                    Err(reason) => return wab::error::argument_error(#arg_name, &reason),
                 };
             }
         };
         arg_conversions.push(arg_conversion);
     }
 
+    if let Err(e) = errors.finish() {
+        return TokenStream::from(e.write_errors());
+    }
+
     let builder = Ident::new(&format!("wab_builder_{}", &fn_name), fn_name.span());
     let wrap = Ident::new(&format!("wab_wrap_{}", &fn_name), fn_name.span());
     let function = Ident::new(&format!("wab_fn_{}", &fn_name), fn_name.span());
 
-    let command_name = attr_args.name;
-    let command_description = attr_args.description;
+    let command_name = attr_args.name.as_str();
+    let command_description = command_description.as_deref().map_or("", String::as_str);
+
+    let settings = match attr_args.settings.quote_builder_calls(&fn_name) {
+        Ok(v) => v,
+        Err(e) => return TokenStream::from(e.write_errors()),
+    };
 
     (quote! {
         #visibility fn #builder() -> wab::CommandBuilder {
             wab::Command::builder()
                 .name(#command_name)
                 .description(#command_description)
-                #(.parameter(#parameters))*
+                #(.try_parameter(#parameters))*
+                #settings
                 .function(#wrap as wab::CommandFunction)
         }
+        #(#kind_assertions)*
         fn #wrap(ctx: wab::CommandContext, mut args: std::collections::HashMap<String, wab::Argument>) -> wab::BoxedFuture<#output> {
             #(#arg_conversions)*
             #function(ctx, #(#fn_parameter_names),*)
@@ -196,6 +573,320 @@ pub fn command(attr: TokenStream, input: TokenStream) -> TokenStream {
     }).into()
 }
 
+#[proc_macro_attribute]
+pub fn user_command(attr: TokenStream, input: TokenStream) -> TokenStream {
+    context_command(attr, input, quote! {User})
+}
+
+#[proc_macro_attribute]
+pub fn message_command(attr: TokenStream, input: TokenStream) -> TokenStream {
+    context_command(attr, input, quote! {Message})
+}
+
+fn context_command(attr: TokenStream, input: TokenStream, kind: TokenStream2) -> TokenStream {
+    let attr_args = match NestedMeta::parse_meta_list(attr.into()) {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(Error::from(e).write_errors());
+        }
+    };
+    let attr_args = match ContextCommandMacroArgs::from_list(&attr_args) {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(e.write_errors());
+        }
+    };
+    let FunctionParse {
+        attributes: _,
+        visibility,
+        name: fn_name,
+        fn_parameters,
+        output,
+        body,
+    } = parse_macro_input!(input as FunctionParse);
+
+    let [_, target] = fn_parameters.as_slice() else {
+        return TokenStream::from(
+            Error::custom("expected a context parameter and a target parameter")
+                .with_span(&fn_name)
+                .write_errors(),
+        );
+    };
+
+    let builder = Ident::new(&format!("wab_builder_{}", &fn_name), fn_name.span());
+    let wrap = Ident::new(&format!("wab_wrap_{}", &fn_name), fn_name.span());
+    let function = Ident::new(&format!("wab_fn_{}", &fn_name), fn_name.span());
+
+    let mut errors = Error::accumulator();
+    validate::validate_context_command_name(&attr_args.name, &mut errors);
+    if let Err(e) = errors.finish() {
+        return TokenStream::from(e.write_errors());
+    }
+    let command_name = attr_args.name.as_str();
+
+    let settings = match attr_args.settings.quote_builder_calls(&fn_name) {
+        Ok(v) => v,
+        Err(e) => return TokenStream::from(e.write_errors()),
+    };
+
+    let target_name = &target.name;
+    let target_type = &target.kind;
+    let target_message = format!("the target of a {kind} command must be a {kind} target type");
+    let target_assertion = quote_spanned! {syn::spanned::Spanned::span(target_type)=>
+        const _: () = assert!(
+            matches!(
+                <#target_type as wab::FromTarget>::KIND,
+                wab::twilight_model::application::command::CommandType::#kind
+            ),
+            #target_message,
+        );
+    };
+
+    (quote! {
+        #visibility fn #builder() -> wab::CommandBuilder {
+            wab::Command::builder()
+                .kind(wab::twilight_model::application::command::CommandType::#kind)
+                .name(#command_name)
+                #settings
+                .function(#wrap as wab::CommandFunction)
+        }
+        #target_assertion
+        fn #wrap(ctx: wab::CommandContext, mut args: std::collections::HashMap<String, wab::Argument>) -> wab::BoxedFuture<#output> {
+            let converted = match args.remove(wab::TARGET_ARGUMENT) {
+                Some(x) => <#target_type as wab::FromTarget>::from_target(x),
+                None => <#target_type as wab::FromTarget>::from_missing(),
+            };
+            let #target_name = match converted {
+                Ok(x) => x,
+                Err(reason) => return wab::error::argument_error(wab::TARGET_ARGUMENT, &reason),
+            };
+            #function(ctx, #target_name)
+        }
+        fn #function(#(#fn_parameters),*) -> wab::BoxedFuture<#output> {
+            Box::pin(async move {
+                #(#body)*
+            })
+        }
+    }).into()
+}
+
+fn argument_macro_args(attributes: &[syn::Attribute]) -> Result<ParameterMacroArgs, Error> {
+    let metas: Vec<NestedMeta> = attributes
+        .iter()
+        .filter(|attr| !attr.path().is_ident("doc"))
+        .map(|attr| NestedMeta::Meta(attr.meta.clone()))
+        .collect();
+    ParameterMacroArgs::from_list(&metas)
+}
+
+fn list_names(name: &str, count: usize) -> Vec<String> {
+    (0..count)
+        .map(|i| match i {
+            0 => String::from(name),
+            i => format!("{name}_{}", i + 1),
+        })
+        .collect()
+}
+
+fn doc_comment(attributes: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attributes
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(doc),
+                        ..
+                    }),
+                ..
+            }) => Some(doc.value().trim().to_string()),
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join(" "))
+    }
+}
+
+fn resolve_bounds(args: &mut ParameterMacroArgs) -> Result<(), Error> {
+    let explicit_min = args.min_value_int.is_some() || args.min_value_number.is_some();
+    let explicit_max = args.max_value_int.is_some() || args.max_value_number.is_some();
+    if (args.min.is_some() && explicit_min) || (args.max.is_some() && explicit_max) {
+        return Err(Error::custom(
+            "`min` and `max` cannot be combined with `min_value_*` and `max_value_*`",
+        ));
+    }
+    for (lit, number) in [
+        (&mut args.min, &mut args.min_value_number),
+        (&mut args.max, &mut args.max_value_number),
+    ] {
+        match lit {
+            Some(syn::Lit::Int(x)) => {
+                x.base10_parse::<i64>()?;
+            }
+            Some(syn::Lit::Float(x)) => {
+                *number = Some(x.base10_parse()?);
+                *lit = None;
+            }
+            Some(_) => return Err(Error::custom("expected a number")),
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+fn quote_bound(ty: &TokenStream2, value: i64, is_min: bool) -> TokenStream2 {
+    let (int, number) = match is_min {
+        true => (quote! {min_value_int}, quote! {min_value_number}),
+        false => (quote! {max_value_int}, quote! {max_value_number}),
+    };
+    let number_value = value as f64;
+    quote! {
+        .#int(matches!(<#ty as wab::FromArgument>::KIND, wab::ParameterType::Integer)
+            .then_some(#value))
+        .#number(matches!(<#ty as wab::FromArgument>::KIND, wab::ParameterType::Float)
+            .then_some(#number_value))
+    }
+}
+
+fn quote_kind_assertions(
+    ty: &TokenStream2,
+    args: &ParameterMacroArgs,
+    span: proc_macro2::Span,
+) -> Vec<TokenStream2> {
+    let mut requirements = Vec::new();
+    if args.min.is_some() || args.max.is_some() {
+        requirements.push((
+            quote! {Integer | wab::ParameterType::Float},
+            "`min` and `max` require an i64 or f64 parameter",
+        ));
+    }
+    if args.min_value_int.is_some() || args.max_value_int.is_some() {
+        requirements.push((
+            quote! {Integer},
+            "`min_value_int` and `max_value_int` require an i64 parameter",
+        ));
+    }
+    if args.min_value_number.is_some() || args.max_value_number.is_some() {
+        requirements.push((
+            quote! {Float},
+            "`min_value_number` and `max_value_number` require an f64 parameter",
+        ));
+    }
+    if args.min_length.is_some() || args.max_length.is_some() {
+        requirements.push((
+            quote! {String},
+            "`min_length` and `max_length` require a String parameter",
+        ));
+    }
+    if !args.channel_types.idents.is_empty() {
+        requirements.push((
+            quote! {Channel},
+            "`channel_types` requires a channel parameter",
+        ));
+    }
+    if args.autocomplete.is_some() {
+        requirements.push((
+            quote! {String | wab::ParameterType::Integer | wab::ParameterType::Float},
+            "`autocomplete` requires a String, i64 or f64 parameter",
+        ));
+    }
+    if let Some(choice) = args.choice.first() {
+        let requirement = if choice.value_int.is_some() {
+            (
+                quote! {Integer},
+                "`value_int` choices require an i64 parameter",
+            )
+        } else if choice.value_number.is_some() {
+            (
+                quote! {Float},
+                "`value_number` choices require an f64 parameter",
+            )
+        } else {
+            (
+                quote! {String},
+                "`value_string` choices require a String parameter",
+            )
+        };
+        requirements.push(requirement);
+    }
+    requirements
+        .into_iter()
+        .map(|(kinds, message)| {
+            quote_spanned! {span=>
+                const _: () = assert!(
+                    matches!(<#ty as wab::FromArgument>::KIND, wab::ParameterType::#kinds),
+                    #message,
+                );
+            }
+        })
+        .collect()
+}
+
+fn quote_permissions(permissions: &str) -> Result<TokenStream2, Error> {
+    let mut flags = Vec::new();
+    for flag in permissions.split('|').map(str::trim) {
+        match syn::parse_str::<Ident>(flag) {
+            Ok(flag) => flags.push(flag),
+            Err(_) => {
+                return Err(Error::custom(format!("invalid permission: '{flag}'")));
+            }
+        }
+    }
+    Ok(quote! {
+        wab::twilight_model::guild::Permissions::empty()
+            #(| wab::twilight_model::guild::Permissions::#flags)*
+    })
+}
+
+fn quote_checks(checks: &IdentList) -> Vec<TokenStream2> {
+    checks
+        .idents
+        .iter()
+        .map(|check| {
+            let name = check.to_string();
+            quote! {
+                wab::Check {
+                    name: String::from(#name),
+                    function: #check as wab::CheckFunction,
+                }
+            }
+        })
+        .collect()
+}
+
+fn vec_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(ty) = ty else {
+        return None;
+    };
+    let segment = ty.path.segments.last()?;
+    if segment.ident != "Vec" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(syn::GenericArgument::Type(inner)) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ty) => ty
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
 #[proc_macro_attribute]
 pub fn box_async(_attr: TokenStream, input: TokenStream) -> TokenStream {
     let FunctionParse {
@@ -255,6 +946,45 @@ impl FromMeta for IdentList {
     }
 }
 
+#[derive(Debug, Default)]
+struct GuildList {
+    ids: Vec<u64>,
+}
+impl GuildList {
+    fn to_scope(&self) -> TokenStream2 {
+        let ids = &self.ids;
+        quote! {
+            wab::CommandScope::Guilds(vec![#(wab::twilight_model::id::Id::new(#ids)),*])
+        }
+    }
+}
+impl FromMeta for GuildList {
+    fn from_list(items: &[NestedMeta]) -> Result<Self, darling::Error> {
+        let mut ids = Vec::new();
+
+        for item in items {
+            match item {
+                NestedMeta::Lit(syn::Lit::Int(lit)) => {
+                    let id = lit.base10_parse::<u64>().map_err(darling::Error::from)?;
+                    if id == 0 {
+                        return Err(
+                            darling::Error::custom("guild id must be non-zero").with_span(lit)
+                        );
+                    }
+                    ids.push(id);
+                }
+                _ => {
+                    return Err(darling::Error::unexpected_type(
+                        &item.to_token_stream().to_string(),
+                    ));
+                }
+            }
+        }
+
+        Ok(GuildList { ids })
+    }
+}
+
 #[derive(Debug, FromMeta)]
 struct GroupMacroArgs {
     category: String,
@@ -262,7 +992,20 @@ struct GroupMacroArgs {
     commands: IdentList,
     #[darling(default)]
     events: IdentList,
+    #[darling(default)]
+    components: IdentList,
+    #[darling(default)]
+    modals: IdentList,
     setup: Option<Ident>,
+    guilds: Option<GuildList>,
+    on_error: Option<Ident>,
+    #[darling(default)]
+    checks: IdentList,
+    permissions: Option<String>,
+    dm: Option<bool>,
+    nsfw: Option<bool>,
+    #[darling(default)]
+    command_groups: IdentList,
 }
 
 #[proc_macro_attribute]
@@ -295,12 +1038,45 @@ pub fn group(attr: TokenStream, input: TokenStream) -> TokenStream {
         .commands
         .idents
         .into_iter()
-        .map(|x| Ident::new(&format!("wab_builder_{}", x.to_string()), x.span()))
+        .map(|x| Ident::new(&format!("wab_builder_{x}"), x.span()))
         .collect();
     let category = attr_args.category;
+    let scope = attr_args.guilds.map(|guilds| {
+        let scope = guilds.to_scope();
+        quote! {.default_scope(#scope)}
+    });
+
+    let on_error = attr_args
+        .on_error
+        .map(|on_error| quote! {.on_error(#on_error as wab::ErrorHandler)});
+
+    let checks = quote_checks(&attr_args.checks);
+    let checks = quote! {vec![#(#checks),*]};
+
+    let permissions = match attr_args.permissions.as_deref().map(quote_permissions) {
+        Some(Ok(permissions)) => quote! {Some(#permissions)},
+        Some(Err(e)) => return TokenStream::from(e.with_span(&struct_name).write_errors()),
+        None => quote! {None},
+    };
+    let dm = quote_option(&attr_args.dm);
+    let nsfw = quote_option(&attr_args.nsfw);
 
     let setup = quote_option(&attr_args.setup);
 
+    let build_command_groups = Ident::new(
+        &format!("wab_group_command_groups_{}", &name_string),
+        struct_name.span(),
+    );
+    let command_groups: Vec<Ident> = attr_args
+        .command_groups
+        .idents
+        .into_iter()
+        .map(|x| {
+            let name = camel_to_snake_case(&x.to_string());
+            Ident::new(&format!("wab_command_group_{}", name), x.span())
+        })
+        .collect();
+
     let build_events = Ident::new(
         &format!("wab_group_events_{}", &name_string),
         struct_name.span(),
@@ -310,38 +1086,327 @@ pub fn group(attr: TokenStream, input: TokenStream) -> TokenStream {
         let variant = Ident::new(&snake_to_camel_case(&event.to_string()), event.span());
         events.push(quote! {
             wab::Event {
-                kind: twilight_model::gateway::event::EventType::#variant,
+                kind: wab::twilight_model::gateway::event::EventType::#variant,
                 function: #event,
             }
         });
     }
 
+    let build_components = Ident::new(
+        &format!("wab_group_components_{}", &name_string),
+        struct_name.span(),
+    );
+    let components: Vec<Ident> = attr_args
+        .components
+        .idents
+        .into_iter()
+        .map(|x| Ident::new(&format!("wab_component_{}", x), x.span()))
+        .collect();
+
+    let build_modals = Ident::new(
+        &format!("wab_group_modals_{}", &name_string),
+        struct_name.span(),
+    );
+    let modals: Vec<Ident> = attr_args
+        .modals
+        .idents
+        .into_iter()
+        .map(|x| Ident::new(&format!("wab_modal_{}", x), x.span()))
+        .collect();
+
     (quote! {
         fn #build_commands() -> Vec<wab::Command> {
             let mut commands = Vec::new();
             #(commands.push(
                 #command_builders()
                     .category(#category)
+                    #scope
+                    #on_error
+                    .group_checks(#checks)
+                    .default_permissions(wab::CommandPermissions {
+                        default_member_permissions: #permissions,
+                        dm_permission: #dm,
+                        nsfw: #nsfw,
+                    })
                     .build()
             );)*
             commands
         }
+        fn #build_command_groups() -> Vec<wab::CommandGroup> {
+            let mut command_groups = Vec::new();
+            #(command_groups.push(#command_groups());)*
+            command_groups
+        }
         fn #build_events() -> Vec<wab::Event> {
             let mut events = Vec::new();
             #(events.push(#events);)*
             events
         }
+        fn #build_components() -> Vec<wab::Component> {
+            let mut components = Vec::new();
+            #(components.push(#components());)*
+            components
+        }
+        fn #build_modals() -> Vec<wab::ModalHandler> {
+            let mut modals = Vec::new();
+            #(modals.push(#modals());)*
+            modals
+        }
         #visibility static #name: wab::Group = wab::Group {
             build_commands: #build_commands,
+            build_command_groups: #build_command_groups,
             build_events: #build_events,
+            build_components: #build_components,
+            build_modals: #build_modals,
             setup: #setup,
         };
     })
     .into()
 }
 
+#[derive(Debug, FromMeta)]
+struct CommandGroupMacroArgs {
+    name: SpannedValue<String>,
+    description: SpannedValue<String>,
+    name_localizations: Option<LocalizationMap>,
+    description_localizations: Option<LocalizationMap>,
+    permissions: Option<String>,
+    dm: Option<bool>,
+    nsfw: Option<bool>,
+}
+
+#[derive(Debug)]
+struct LocalizationMap {
+    entries: Vec<(syn::LitStr, syn::LitStr)>,
+}
+impl LocalizationMap {
+    fn quote(map: &Option<Self>) -> TokenStream2 {
+        match map {
+            Some(map) => {
+                let locales = map.entries.iter().map(|(locale, _)| locale);
+                let values = map.entries.iter().map(|(_, value)| value);
+                quote! {
+                    Some(std::collections::HashMap::from([
+                        #((String::from(#locales), String::from(#values))),*
+                    ]))
+                }
+            }
+            None => quote! {None},
+        }
+    }
+}
+impl FromMeta for LocalizationMap {
+    fn from_meta(item: &syn::Meta) -> Result<Self, Error> {
+        let syn::Meta::List(list) = item else {
+            return Err(Error::unsupported_format(
+                "expected a list of `\"locale\" = \"text\"`",
+            ));
+        };
+        let entries = list.parse_args_with(
+            syn::punctuated::Punctuated::<LocalizationEntry, syn::Token![,]>::parse_terminated,
+        )?;
+        Ok(Self {
+            entries: entries.into_iter().map(|x| (x.locale, x.value)).collect(),
+        })
+    }
+}
+
+struct LocalizationEntry {
+    locale: syn::LitStr,
+    value: syn::LitStr,
+}
+impl syn::parse::Parse for LocalizationEntry {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let locale = input.parse()?;
+        input.parse::<syn::Token![=]>()?;
+        let value = input.parse()?;
+        Ok(Self { locale, value })
+    }
+}
+
 #[proc_macro_attribute]
-pub fn event(_attr: TokenStream, input: TokenStream) -> TokenStream {
+pub fn command_group(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let attr_args = match NestedMeta::parse_meta_list(attr.into()) {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(Error::from(e).write_errors());
+        }
+    };
+    let attr_args = match CommandGroupMacroArgs::from_list(&attr_args) {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(e.write_errors());
+        }
+    };
+    let StructParse {
+        visibility,
+        name: struct_name,
+    } = parse_macro_input!(input as StructParse);
+
+    let mut errors = Error::accumulator();
+    validate::validate_command_name(&attr_args.name, &mut errors);
+    validate::validate_description(&attr_args.description, &mut errors);
+    if attr_args.name.split(' ').count() > 2 {
+        errors.push(
+            Error::custom("command groups can have at most 2 parts")
+                .with_span(&attr_args.name.span()),
+        );
+    }
+    let subgroup = attr_args.name.contains(' ');
+    if subgroup
+        && (attr_args.permissions.is_some() || attr_args.dm.is_some() || attr_args.nsfw.is_some())
+    {
+        errors.push(
+            Error::custom("subgroups cannot set permissions, Discord only allows them on roots")
+                .with_span(&attr_args.name.span()),
+        );
+    }
+    for (_, name) in attr_args.name_localizations.iter().flat_map(|x| &x.entries) {
+        validate::validate_name(&name.value(), name.span(), &mut errors);
+    }
+    for (_, description) in attr_args
+        .description_localizations
+        .iter()
+        .flat_map(|x| &x.entries)
+    {
+        let description = SpannedValue::new(description.value(), description.span());
+        validate::validate_description(&description, &mut errors);
+    }
+    let permissions = match attr_args.permissions.as_deref().map(quote_permissions) {
+        Some(Ok(permissions)) => quote! {Some(#permissions)},
+        Some(Err(e)) => {
+            errors.push(e.with_span(&attr_args.name.span()));
+            quote! {None}
+        }
+        None => quote! {None},
+    };
+    if let Err(e) = errors.finish() {
+        return TokenStream::from(e.write_errors());
+    }
+
+    let function = Ident::new(
+        &format!(
+            "wab_command_group_{}",
+            camel_to_snake_case(&struct_name.to_string())
+        ),
+        struct_name.span(),
+    );
+
+    let name = attr_args.name.as_str();
+    let description = attr_args.description.as_str();
+    let name_localizations = LocalizationMap::quote(&attr_args.name_localizations);
+    let description_localizations = LocalizationMap::quote(&attr_args.description_localizations);
+    let dm = quote_option(&attr_args.dm);
+    let nsfw = quote_option(&attr_args.nsfw);
+
+    (quote! {
+        #visibility fn #function() -> wab::CommandGroup {
+            wab::CommandGroup {
+                name: String::from(#name),
+                description: String::from(#description),
+                name_localizations: #name_localizations,
+                description_localizations: #description_localizations,
+                permissions: wab::CommandPermissions {
+                    default_member_permissions: #permissions,
+                    dm_permission: #dm,
+                    nsfw: #nsfw,
+                },
+            }
+        }
+    })
+    .into()
+}
+
+#[derive(Debug, FromMeta)]
+struct ComponentMacroArgs {
+    prefix: String,
+}
+
+#[proc_macro_attribute]
+pub fn component(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let attr_args = match NestedMeta::parse_meta_list(attr.into()) {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(Error::from(e).write_errors());
+        }
+    };
+    let attr_args = match ComponentMacroArgs::from_list(&attr_args) {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(e.write_errors());
+        }
+    };
+    let FunctionParse {
+        attributes: _,
+        visibility,
+        name: fn_name,
+        fn_parameters,
+        output,
+        body,
+    } = parse_macro_input!(input as FunctionParse);
+
+    let Some((_, data_parameters)) = fn_parameters.split_first() else {
+        return TokenStream::from(
+            Error::custom("expected a ComponentContext parameter")
+                .with_span(&fn_name)
+                .write_errors(),
+        );
+    };
+    let data_names: Vec<&Ident> = data_parameters.iter().map(|x| &x.name).collect();
+    let data_types: Vec<&Type> = data_parameters.iter().map(|x| &x.kind).collect();
+
+    let component = Ident::new(&format!("wab_component_{}", &fn_name), fn_name.span());
+    let wrap = Ident::new(&format!("wab_wrap_{}", &fn_name), fn_name.span());
+    let function = Ident::new(&format!("wab_fn_{}", &fn_name), fn_name.span());
+    let custom_id = Ident::new(&format!("{}_custom_id", &fn_name), fn_name.span());
+
+    let prefix = attr_args.prefix;
+
+    (quote! {
+        #visibility fn #component() -> wab::Component {
+            wab::Component {
+                prefix: String::from(#prefix),
+                function: #wrap as wab::ComponentFunction,
+            }
+        }
+        #visibility fn #custom_id(#(#data_names: #data_types),*) -> Result<String, wab::CustomIdError> {
+            wab::CustomId::new(#prefix)
+                #(.arg(#data_names))*
+                .build()
+        }
+        fn #wrap(ctx: wab::ComponentContext, args: Vec<String>) -> wab::BoxedFuture<#output> {
+            let mut args = args.into_iter();
+            #(
+                let #data_names = match args.next().map(|x| x.parse::<#data_types>()) {
+                    Some(Ok(x)) => x,
+                    _ => return wab::error::argument_error(stringify!(#data_names), "component data mismatched"),
+                };
+            )*
+            #function(ctx, #(#data_names),*)
+        }
+        fn #function(#(#fn_parameters),*) -> wab::BoxedFuture<#output> {
+            Box::pin(async move {
+                #(#body)*
+            })
+        }
+    })
+    .into()
+}
+
+#[proc_macro_attribute]
+pub fn modal(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let attr_args = match NestedMeta::parse_meta_list(attr.into()) {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(Error::from(e).write_errors());
+        }
+    };
+    let attr_args = match ComponentMacroArgs::from_list(&attr_args) {
+        Ok(v) => v,
+        Err(e) => {
+            return TokenStream::from(e.write_errors());
+        }
+    };
     let FunctionParse {
         attributes: _,
         visibility,
@@ -351,20 +1416,110 @@ pub fn event(_attr: TokenStream, input: TokenStream) -> TokenStream {
         body,
     } = parse_macro_input!(input as FunctionParse);
 
-    assert!(fn_parameters.len() == 1);
+    let [_, form, data_parameters @ ..] = fn_parameters.as_slice() else {
+        return TokenStream::from(
+            Error::custom("expected a ModalContext parameter and a form parameter")
+                .with_span(&fn_name)
+                .write_errors(),
+        );
+    };
+    let form_name = &form.name;
+    let form_type = &form.kind;
+    let data_names: Vec<&Ident> = data_parameters.iter().map(|x| &x.name).collect();
+    let data_types: Vec<&Type> = data_parameters.iter().map(|x| &x.kind).collect();
+
+    let modal = Ident::new(&format!("wab_modal_{}", &fn_name), fn_name.span());
+    let wrap = Ident::new(&format!("wab_wrap_{}", &fn_name), fn_name.span());
+    let function = Ident::new(&format!("wab_fn_{}", &fn_name), fn_name.span());
+    let custom_id = Ident::new(&format!("{}_custom_id", &fn_name), fn_name.span());
+
+    let prefix = attr_args.prefix;
+
+    (quote! {
+        #visibility fn #modal() -> wab::ModalHandler {
+            wab::ModalHandler {
+                prefix: String::from(#prefix),
+                function: #wrap as wab::ModalFunction,
+            }
+        }
+        #visibility fn #custom_id(#(#data_names: #data_types),*) -> Result<String, wab::CustomIdError> {
+            wab::CustomId::new(#prefix)
+                #(.arg(#data_names))*
+                .build()
+        }
+        fn #wrap(ctx: wab::ModalContext, args: Vec<String>) -> wab::BoxedFuture<#output> {
+            let #form_name = match <#form_type as wab::Modal>::parse(&ctx.fields()) {
+                Some(x) => x,
+                None => return wab::error::argument_error(stringify!(#form_name), "modal fields mismatched"),
+            };
+            let mut args = args.into_iter();
+            #(
+                let #data_names = match args.next().map(|x| x.parse::<#data_types>()) {
+                    Some(Ok(x)) => x,
+                    _ => return wab::error::argument_error(stringify!(#data_names), "modal data mismatched"),
+                };
+            )*
+            #function(ctx, #form_name, #(#data_names),*)
+        }
+        fn #function(#(#fn_parameters),*) -> wab::BoxedFuture<#output> {
+            Box::pin(async move {
+                #(#body)*
+            })
+        }
+    })
+    .into()
+}
+
+#[proc_macro_derive(Choices, attributes(choice))]
+pub fn derive_choices(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    match choices::derive(&input) {
+        Ok(v) => v.into(),
+        Err(e) => TokenStream::from(e.write_errors()),
+    }
+}
+
+#[proc_macro_derive(Modal, attributes(modal, input))]
+pub fn derive_modal(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    match modal::derive(&input) {
+        Ok(v) => v.into(),
+        Err(e) => TokenStream::from(e.write_errors()),
+    }
+}
+
+#[proc_macro_attribute]
+pub fn event(_attr: TokenStream, input: TokenStream) -> TokenStream {
+    let FunctionParse {
+        attributes: _,
+        visibility,
+        name: fn_name,
+        fn_parameters,
+        output,
+        body,
+    } = parse_macro_input!(input as FunctionParse);
 
-    let parameter = &fn_parameters[0];
+    let [parameter] = fn_parameters.as_slice() else {
+        return TokenStream::from(
+            Error::custom("expected a single event parameter")
+                .with_span(&fn_name)
+                .write_errors(),
+        );
+    };
     let name = &parameter.name;
     let kind = &parameter.kind;
 
     let variant = Ident::new(&snake_to_camel_case(&fn_name.to_string()), name.span());
 
     (quote! {
-        #visibility fn #fn_name(#name: std::sync::Arc<twilight_model::gateway::event::Event>) -> wab::BoxedFuture<#output> {
+        #visibility fn #fn_name(#name: std::sync::Arc<wab::twilight_model::gateway::event::Event>) -> wab::BoxedFuture<#output> {
             Box::pin(async move {
                 let #name: #kind = match #name.as_ref() {
-                    twilight_model::gateway::event::Event::#variant(x) => x,
-                    _ => panic!(),
+                    wab::twilight_model::gateway::event::Event::#variant(x) => x,
+                    event => {
+                        wab::error::unexpected_event(stringify!(#fn_name), event.kind());
+                        return;
+                    }
                 };
                 #(#body)*
             })
@@ -397,3 +1552,115 @@ fn snake_to_camel_case(s: &str) -> String {
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse::Parser;
+
+    fn args(source: &str) -> ParameterMacroArgs {
+        let metas = NestedMeta::parse_meta_list(source.parse().unwrap()).unwrap();
+        ParameterMacroArgs::from_list(&metas).unwrap()
+    }
+
+    #[test]
+    fn resolves_bounds() {
+        let mut float = args("min = 1.5, max = 3");
+        resolve_bounds(&mut float).unwrap();
+        assert!(float.min.is_none());
+        assert_eq!(float.min_value_number, Some(1.5));
+        assert_eq!(float.max_value(), Some(3));
+
+        let error = resolve_bounds(&mut args("min = 1, min_value_int = 2")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "`min` and `max` cannot be combined with `min_value_*` and `max_value_*`"
+        );
+        let error = resolve_bounds(&mut args(r#"max = "ten""#)).unwrap_err();
+        assert_eq!(error.to_string(), "expected a number");
+    }
+
+    #[test]
+    fn infers_argument_metadata() {
+        let attributes = syn::Attribute::parse_outer
+            .parse_str("/// the size\n///\n///   in pixels\n#[min = 1]\n#[max_count = 3]")
+            .unwrap();
+        assert_eq!(
+            doc_comment(&attributes).as_deref(),
+            Some("the size in pixels")
+        );
+        let args = argument_macro_args(&attributes).unwrap();
+        assert!(args.description.is_none());
+        assert_eq!(args.min_value(), Some(1));
+        assert_eq!(args.max_count, Some(3));
+
+        let attributes = syn::Attribute::parse_outer
+            .parse_str(r#"#[description = "the size"] #[rest]"#)
+            .unwrap();
+        assert_eq!(doc_comment(&attributes), None);
+        let args = argument_macro_args(&attributes).unwrap();
+        assert_eq!(
+            args.description.as_deref().map(String::as_str),
+            Some("the size")
+        );
+        assert!(args.rest);
+
+        let attributes = syn::Attribute::parse_outer
+            .parse_str("#[colour = 1]")
+            .unwrap();
+        let error = argument_macro_args(&attributes).unwrap_err();
+        assert_eq!(error.span().source_text().as_deref(), Some("colour = 1"));
+    }
+
+    #[test]
+    fn numbers_list_options() {
+        assert_eq!(list_names("role", 1), vec!["role"]);
+        assert_eq!(list_names("role", 3), vec!["role", "role_2", "role_3"]);
+    }
+
+    #[test]
+    fn asserts_parameter_kinds() {
+        let name: Ident = syn::parse_str("size").unwrap();
+        let assertions = quote_kind_assertions(
+            &quote! {i64},
+            &args("min_length = 1, autocomplete = complete"),
+            name.span(),
+        );
+        let expected = [
+            quote! {
+                const _: () = assert!(
+                    matches!(<i64 as wab::FromArgument>::KIND, wab::ParameterType::String),
+                    "`min_length` and `max_length` require a String parameter",
+                );
+            },
+            quote! {
+                const _: () = assert!(
+                    matches!(
+                        <i64 as wab::FromArgument>::KIND,
+                        wab::ParameterType::String
+                            | wab::ParameterType::Integer
+                            | wab::ParameterType::Float
+                    ),
+                    "`autocomplete` requires a String, i64 or f64 parameter",
+                );
+            },
+        ];
+        assert_eq!(assertions.len(), expected.len());
+        for (assertion, expected) in assertions.iter().zip(expected) {
+            assert_eq!(assertion.to_string(), expected.to_string());
+            let span = assertion.clone().into_iter().next().unwrap().span();
+            assert_eq!(span.source_text().as_deref(), Some("size"));
+        }
+
+        let assertions = quote_kind_assertions(
+            &quote! {f64},
+            &args(r#"choice(name = "a", value_int = 1)"#),
+            name.span(),
+        );
+        assert_eq!(assertions.len(), 1);
+        assert!(assertions[0]
+            .to_string()
+            .contains("\"`value_int` choices require an i64 parameter\""));
+        assert!(quote_kind_assertions(&quote! {i64}, &args(""), name.span()).is_empty());
+    }
+}
@@ -0,0 +1,227 @@
+use darling::{
+    ast::Data,
+    error::Accumulator,
+    util::{Ignored, SpannedValue},
+    Error, FromDeriveInput, FromField,
+};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{DeriveInput, GenericArgument, Ident, PathArguments, Type};
+
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(modal), supports(struct_named))]
+struct ModalArgs {
+    ident: Ident,
+    data: Data<Ignored, InputArgs>,
+    title: SpannedValue<String>,
+}
+
+#[derive(Debug, FromField)]
+#[darling(attributes(input))]
+struct InputArgs {
+    ident: Option<Ident>,
+    ty: Type,
+    label: Option<SpannedValue<String>>,
+    style: Option<String>,
+    required: Option<bool>,
+    placeholder: Option<SpannedValue<String>>,
+    value: Option<SpannedValue<String>>,
+    min_length: Option<SpannedValue<u16>>,
+    max_length: Option<SpannedValue<u16>>,
+}
+
+const MAX_TITLE_LENGTH: usize = 45;
+const MAX_LABEL_LENGTH: usize = 45;
+const MAX_PLACEHOLDER_LENGTH: usize = 100;
+const MAX_INPUT_LENGTH: u16 = 4000;
+const MAX_COMPONENTS: usize = 5;
+
+pub fn derive(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let args = ModalArgs::from_derive_input(input)?;
+    let name = &args.ident;
+    let title = args.title.as_str();
+    let fields = args.data.take_struct().unwrap().fields;
+
+    let mut errors = Error::accumulator();
+    let length = title.chars().count();
+    if length == 0 || length > MAX_TITLE_LENGTH {
+        errors.push(
+            Error::custom(format!("title must be 1-{MAX_TITLE_LENGTH} characters"))
+                .with_span(&args.title.span()),
+        );
+    }
+    if fields.is_empty() || fields.len() > MAX_COMPONENTS {
+        errors.push(
+            Error::custom(format!(
+                "a modal must have between 1 and {MAX_COMPONENTS} fields"
+            ))
+            .with_span(name),
+        );
+    }
+
+    let mut components = Vec::new();
+    let mut parsers = Vec::new();
+    let mut field_names = Vec::new();
+
+    for field in fields {
+        let field_name = field.ident.clone().unwrap();
+        let custom_id = field_name.to_string();
+        let label = field
+            .label
+            .clone()
+            .unwrap_or_else(|| SpannedValue::new(custom_id.clone(), field_name.span()));
+        validate_input(&field, &label, &mut errors);
+        let label = label.as_str();
+        let (optional, inner) = match option_inner(&field.ty) {
+            Some(inner) => (true, inner),
+            None => (false, &field.ty),
+        };
+        let required = field.required.unwrap_or(!optional);
+        let style = match field.style.as_deref() {
+            None | Some("short") => {
+                quote! {wab::twilight_model::channel::message::component::TextInputStyle::Short}
+            }
+            Some("paragraph") => {
+                quote! {wab::twilight_model::channel::message::component::TextInputStyle::Paragraph}
+            }
+            Some(_) => {
+                errors.push(
+                    Error::custom("style must be \"short\" or \"paragraph\"")
+                        .with_span(&field_name),
+                );
+                continue;
+            }
+        };
+        let placeholder = quote_string_option(&field.placeholder.as_deref().cloned());
+        let value = quote_string_option(&field.value.as_deref().cloned());
+        let min_length = crate::quote_option(&field.min_length.as_deref().copied());
+        let max_length = crate::quote_option(&field.max_length.as_deref().copied());
+
+        components.push(quote! {
+            wab::twilight_model::channel::message::Component::ActionRow(
+                wab::twilight_model::channel::message::component::ActionRow {
+                    components: vec![wab::twilight_model::channel::message::Component::TextInput(
+                        wab::twilight_model::channel::message::component::TextInput {
+                            custom_id: String::from(#custom_id),
+                            label: String::from(#label),
+                            max_length: #max_length,
+                            min_length: #min_length,
+                            placeholder: #placeholder,
+                            required: Some(#required),
+                            style: #style,
+                            value: #value,
+                        },
+                    )],
+                },
+            )
+        });
+
+        parsers.push(if !optional {
+            quote! {
+                let #field_name = fields.get(#custom_id)?.parse::<#inner>().ok()?;
+            }
+        } else {
+            quote! {
+                let #field_name = match fields.get(#custom_id).filter(|x| !x.is_empty()) {
+                    Some(x) => Some(x.parse::<#inner>().ok()?),
+                    None => None,
+                };
+            }
+        });
+        field_names.push(field_name);
+    }
+    errors.finish()?;
+
+    Ok(quote! {
+        impl wab::Modal for #name {
+            fn title() -> String {
+                String::from(#title)
+            }
+            fn create_components() -> Vec<wab::twilight_model::channel::message::Component> {
+                vec![#(#components),*]
+            }
+            fn parse(fields: &std::collections::HashMap<String, String>) -> Option<Self> {
+                #(#parsers)*
+                Some(Self {
+                    #(#field_names),*
+                })
+            }
+        }
+    })
+}
+
+fn validate_input(field: &InputArgs, label: &SpannedValue<String>, errors: &mut Accumulator) {
+    let length = label.chars().count();
+    if length == 0 || length > MAX_LABEL_LENGTH {
+        errors.push(
+            Error::custom(format!("label must be 1-{MAX_LABEL_LENGTH} characters"))
+                .with_span(&label.span()),
+        );
+    }
+    if let Some(placeholder) = &field.placeholder {
+        if placeholder.chars().count() > MAX_PLACEHOLDER_LENGTH {
+            errors.push(
+                Error::custom(format!(
+                    "placeholder must be at most {MAX_PLACEHOLDER_LENGTH} characters"
+                ))
+                .with_span(&placeholder.span()),
+            );
+        }
+    }
+    if let Some(value) = &field.value {
+        if value.chars().count() > usize::from(MAX_INPUT_LENGTH) {
+            errors.push(
+                Error::custom(format!(
+                    "value must be at most {MAX_INPUT_LENGTH} characters"
+                ))
+                .with_span(&value.span()),
+            );
+        }
+    }
+    for length in [&field.min_length, &field.max_length].into_iter().flatten() {
+        if **length > MAX_INPUT_LENGTH {
+            errors.push(
+                Error::custom(format!("lengths must be at most {MAX_INPUT_LENGTH}"))
+                    .with_span(&length.span()),
+            );
+        }
+    }
+    if let Some(max_length) = &field.max_length {
+        if **max_length == 0 {
+            errors.push(
+                Error::custom("`max_length` must be at least 1").with_span(&max_length.span()),
+            );
+        }
+    }
+    if let (Some(min), Some(max)) = (&field.min_length, &field.max_length) {
+        if **min > **max {
+            errors.push(
+                Error::custom("`min_length` must not exceed `max_length`").with_span(&min.span()),
+            );
+        }
+    }
+}
+
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first()? {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn quote_string_option(option: &Option<String>) -> TokenStream2 {
+    match option {
+        Some(x) => quote! {Some(String::from(#x))},
+        None => quote! {None},
+    }
+}
@@ -6,6 +6,7 @@ use syn::{
 };
 
 pub struct FunctionParameter {
+    pub attributes: Vec<Attribute>,
     pub mutable: bool,
     pub name: Ident,
     pub kind: Type,
@@ -13,6 +14,7 @@ pub struct FunctionParameter {
 impl ToTokens for FunctionParameter {
     fn to_tokens(&self, stream: &mut TokenStream2) {
         let Self {
+            attributes: _,
             mutable,
             name,
             kind,
@@ -46,6 +48,7 @@ impl Parse for FunctionParse {
         parenthesized!(content in input);
         let mut fn_parameters = Vec::new();
         while !content.is_empty() {
+            let attributes = content.call(Attribute::parse_outer)?;
             let mut mutable = false;
             if content.peek(Token![mut]) {
                 content.parse::<Token![mut]>()?;
@@ -56,6 +59,7 @@ impl Parse for FunctionParse {
             content.parse::<Token![:]>()?;
             let kind: Type = content.parse()?;
             fn_parameters.push(FunctionParameter {
+                attributes,
                 mutable,
                 name,
                 kind,
@@ -67,7 +71,7 @@ impl Parse for FunctionParse {
 
         let output = match input.parse::<ReturnType>()? {
             ReturnType::Type(_, ty) => (*ty).clone(),
-            ReturnType::Default => syn::Type::Verbatim(quote!{()}),
+            ReturnType::Default => syn::Type::Verbatim(quote! {()}),
         };
 
         let body;
@@ -87,7 +91,7 @@ impl Parse for FunctionParse {
 
 pub struct StructParse {
     pub visibility: Visibility,
-    pub name: Ident
+    pub name: Ident,
 }
 impl Parse for StructParse {
     fn parse(input: ParseStream) -> syn::Result<Self> {
@@ -95,9 +99,6 @@ impl Parse for StructParse {
         input.parse::<Token![struct]>()?;
         let name: Ident = input.parse()?;
         input.parse::<Token![;]>()?;
-        Ok(Self {
-            visibility,
-            name
-        })
+        Ok(Self { visibility, name })
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,434 @@
+use crate::{ChoiceMacroArgs, DefaultLiteral, DefaultValue, ParameterMacroArgs};
+use darling::{error::Accumulator, util::SpannedValue, Error};
+
+const MAX_NAME_LENGTH: usize = 32;
+const MAX_DESCRIPTION_LENGTH: usize = 100;
+const MAX_CHOICES: usize = 25;
+const MAX_LENGTH: i32 = 6000;
+const MAX_OPTIONS: usize = 25;
+
+pub fn validate_command_name(name: &SpannedValue<String>, errors: &mut Accumulator) {
+    let parts: Vec<&str> = name.split(' ').collect();
+    if parts.len() > 3 {
+        errors
+            .push(Error::custom("command names can have at most 3 parts").with_span(&name.span()));
+    }
+    for part in parts {
+        validate_name(part, name.span(), errors);
+    }
+}
+
+pub fn validate_context_command_name(name: &SpannedValue<String>, errors: &mut Accumulator) {
+    let length = name.chars().count();
+    if length == 0 || length > MAX_NAME_LENGTH {
+        errors.push(
+            Error::custom(format!("name must be 1-{MAX_NAME_LENGTH} characters"))
+                .with_span(&name.span()),
+        );
+    }
+}
+
+pub fn validate_name(name: &str, span: proc_macro2::Span, errors: &mut Accumulator) {
+    let length = name.chars().count();
+    if length == 0 || length > MAX_NAME_LENGTH {
+        errors.push(
+            Error::custom(format!(
+                "name '{name}' must be 1-{MAX_NAME_LENGTH} characters"
+            ))
+            .with_span(&span),
+        );
+    }
+    if !name
+        .chars()
+        .all(|c| c == '-' || c == '_' || c.is_alphanumeric())
+    {
+        errors.push(
+            Error::custom(format!(
+                "name '{name}' may only contain letters, numbers, '-' and '_'"
+            ))
+            .with_span(&span),
+        );
+    }
+    if name.chars().any(|c| c.is_uppercase()) {
+        errors.push(Error::custom(format!("name '{name}' must be lowercase")).with_span(&span));
+    }
+}
+
+pub fn validate_description(description: &SpannedValue<String>, errors: &mut Accumulator) {
+    let length = description.chars().count();
+    if length == 0 || length > MAX_DESCRIPTION_LENGTH {
+        errors.push(
+            Error::custom(format!(
+                "description must be 1-{MAX_DESCRIPTION_LENGTH} characters"
+            ))
+            .with_span(&description.span()),
+        );
+    }
+}
+
+pub fn validate_parameter(
+    name: &SpannedValue<String>,
+    description: Option<&SpannedValue<String>>,
+    args: &ParameterMacroArgs,
+    errors: &mut Accumulator,
+) {
+    let span = name.span();
+
+    validate_name(name, span, errors);
+    if let Some(description) = description {
+        validate_description(description, errors);
+    }
+
+    if args.choice.len() > MAX_CHOICES {
+        errors.push(
+            Error::custom(format!("at most {MAX_CHOICES} choices are allowed")).with_span(&span),
+        );
+    }
+    for choice in args.choice.iter() {
+        validate_choice(choice, errors);
+    }
+    let value_type = |choice: &ChoiceMacroArgs| {
+        (
+            choice.value_string.is_some(),
+            choice.value_int.is_some(),
+            choice.value_number.is_some(),
+        )
+    };
+    if let Some(first) = args.choice.first() {
+        if let Some(choice) = args
+            .choice
+            .iter()
+            .find(|x| value_type(x) != value_type(first))
+        {
+            errors.push(
+                Error::custom("choices must all have the same value type")
+                    .with_span(&choice.name.span()),
+            );
+        }
+    }
+
+    if args.min.is_some() || args.max.is_some() {
+        let min = args.min_value().map(|x| x as f64).or(args.min_value_number);
+        let max = args.max_value().map(|x| x as f64).or(args.max_value_number);
+        if let (Some(min), Some(max)) = (min, max) {
+            if min > max {
+                errors.push(Error::custom("`min` must not exceed `max`").with_span(&span));
+            }
+        }
+    }
+    if let (Some(min), Some(max)) = (args.min_value_int, args.max_value_int) {
+        if min > max {
+            errors.push(
+                Error::custom("`min_value_int` must not exceed `max_value_int`").with_span(&span),
+            );
+        }
+    }
+    if let (Some(min), Some(max)) = (args.min_value_number, args.max_value_number) {
+        if min > max {
+            errors.push(
+                Error::custom("`min_value_number` must not exceed `max_value_number`")
+                    .with_span(&span),
+            );
+        }
+    }
+    for length in [args.min_length, args.max_length].into_iter().flatten() {
+        if !(0..=MAX_LENGTH).contains(&length) {
+            errors.push(
+                Error::custom(format!("lengths must be between 0 and {MAX_LENGTH}"))
+                    .with_span(&span),
+            );
+        }
+    }
+    if let (Some(min), Some(max)) = (args.min_length, args.max_length) {
+        if min > max {
+            errors
+                .push(Error::custom("`min_length` must not exceed `max_length`").with_span(&span));
+        }
+    }
+
+    if args.autocomplete.is_some() && !args.choice.is_empty() {
+        errors
+            .push(Error::custom("`autocomplete` cannot be combined with choices").with_span(&span));
+    }
+}
+
+pub fn validate_list(
+    name: &SpannedValue<String>,
+    (min, max): (usize, Option<usize>),
+    rest: bool,
+    errors: &mut Accumulator,
+) {
+    let span = name.span();
+    match max {
+        Some(max) if min > max => {
+            errors.push(Error::custom("`min_count` must not exceed `max_count`").with_span(&span));
+        }
+        Some(max) if !rest && (max == 0 || max > MAX_OPTIONS) => {
+            errors.push(
+                Error::custom(format!("`max_count` must be between 1 and {MAX_OPTIONS}"))
+                    .with_span(&span),
+            );
+        }
+        Some(max) if !rest => {
+            validate_name(&format!("{}_{max}", name.as_str()), span, errors);
+        }
+        None if !rest => {
+            errors.push(
+                Error::custom("`Vec` arguments need a `max_count`, or `rest`").with_span(&span),
+            );
+        }
+        _ => {}
+    }
+}
+
+pub fn validate_default(
+    name: &SpannedValue<String>,
+    description: Option<&SpannedValue<String>>,
+    default: &DefaultValue,
+    args: &ParameterMacroArgs,
+    errors: &mut Accumulator,
+) {
+    let span = name.span();
+
+    if let Some(description) = description {
+        let length =
+            description.chars().count() + " (default: )".len() + default.help().chars().count();
+        if length > MAX_DESCRIPTION_LENGTH {
+            errors.push(
+                Error::custom(format!(
+                    "description and default must fit in {MAX_DESCRIPTION_LENGTH} characters"
+                ))
+                .with_span(&description.span()),
+            );
+        }
+    }
+
+    let Some(literal) = default.literal() else {
+        return;
+    };
+    let in_range = match &literal {
+        DefaultLiteral::Int(x) => {
+            args.min_value().is_none_or(|min| min <= *x)
+                && args.max_value().is_none_or(|max| *x <= max)
+                && args.min_value_int.is_none_or(|min| min <= *x)
+                && args.max_value_int.is_none_or(|max| *x <= max)
+                && args.min_value_number.is_none_or(|min| min <= *x as f64)
+                && args.max_value_number.is_none_or(|max| *x as f64 <= max)
+        }
+        DefaultLiteral::Float(x) => {
+            args.min_value().is_none_or(|min| min as f64 <= *x)
+                && args.max_value().is_none_or(|max| *x <= max as f64)
+                && args.min_value_number.is_none_or(|min| min <= *x)
+                && args.max_value_number.is_none_or(|max| *x <= max)
+        }
+        DefaultLiteral::Str(x) => {
+            let length = x.chars().count() as i32;
+            args.min_length.is_none_or(|min| min <= length)
+                && args.max_length.is_none_or(|max| length <= max)
+        }
+        DefaultLiteral::Bool(_) => true,
+    };
+    if !in_range {
+        errors.push(Error::custom("`default` is outside the allowed range").with_span(&span));
+    }
+
+    if !args.choice.is_empty() {
+        let matches_choice = args.choice.iter().any(|choice| match &literal {
+            DefaultLiteral::Int(x) => {
+                choice.value_int == Some(*x) || choice.value_number == Some(*x as f64)
+            }
+            DefaultLiteral::Float(x) => choice.value_number == Some(*x),
+            DefaultLiteral::Str(x) => choice.value_string.as_ref() == Some(x),
+            DefaultLiteral::Bool(_) => false,
+        });
+        if !matches_choice {
+            errors.push(Error::custom("`default` must be one of the choices").with_span(&span));
+        }
+    }
+}
+
+fn validate_choice(choice: &ChoiceMacroArgs, errors: &mut Accumulator) {
+    let span = choice.name.span();
+
+    let length = choice.name.chars().count();
+    if length == 0 || length > MAX_DESCRIPTION_LENGTH {
+        errors.push(
+            Error::custom(format!(
+                "choice names must be 1-{MAX_DESCRIPTION_LENGTH} characters"
+            ))
+            .with_span(&span),
+        );
+    }
+
+    let values = [
+        choice.value_string.is_some(),
+        choice.value_int.is_some(),
+        choice.value_number.is_some(),
+    ];
+    match values.iter().filter(|x| **x).count() {
+        0 => errors.push(
+            Error::custom("expected one of `value_string`, `value_int` or `value_number`")
+                .with_span(&span),
+        ),
+        1 => {}
+        _ => errors.push(Error::custom("choices can only have one value").with_span(&span)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use darling::{ast::NestedMeta, FromMeta};
+
+    fn spanned(source: &str) -> SpannedValue<String> {
+        let lit: syn::LitStr = syn::parse_str(source).unwrap();
+        SpannedValue::new(lit.value(), lit.span())
+    }
+
+    fn args(source: &str) -> ParameterMacroArgs {
+        let metas = NestedMeta::parse_meta_list(source.parse().unwrap()).unwrap();
+        ParameterMacroArgs::from_list(&metas).unwrap()
+    }
+
+    fn errors(check: impl FnOnce(&mut Accumulator)) -> Vec<(String, String)> {
+        let mut errors = Error::accumulator();
+        check(&mut errors);
+        match errors.finish() {
+            Ok(()) => Vec::new(),
+            Err(error) => error
+                .into_iter()
+                .map(|x| (x.to_string(), x.span().source_text().unwrap()))
+                .collect(),
+        }
+    }
+
+    fn error(message: &str, source: &str) -> (String, String) {
+        (String::from(message), String::from(source))
+    }
+
+    #[test]
+    fn checks_command_names() {
+        assert!(errors(|e| validate_command_name(&spanned(r#""admin ban""#), e)).is_empty());
+        assert_eq!(
+            errors(|e| validate_command_name(&spanned(r#""a b c d""#), e)),
+            vec![error(
+                "command names can have at most 3 parts",
+                r#""a b c d""#
+            )]
+        );
+        assert_eq!(
+            errors(|e| validate_command_name(&spanned(r#""Ban user!""#), e)),
+            vec![
+                error("name 'Ban' must be lowercase", r#""Ban user!""#),
+                error(
+                    "name 'user!' may only contain letters, numbers, '-' and '_'",
+                    r#""Ban user!""#
+                ),
+            ]
+        );
+        let long = format!(r#""{}""#, "a".repeat(33));
+        assert_eq!(
+            errors(|e| validate_command_name(&spanned(&long), e)),
+            vec![error(
+                &format!("name '{}' must be 1-32 characters", "a".repeat(33)),
+                &long
+            )]
+        );
+        assert!(
+            errors(|e| validate_context_command_name(&spanned(r#""User Info""#), e)).is_empty()
+        );
+    }
+
+    #[test]
+    fn checks_descriptions() {
+        assert!(errors(|e| validate_description(&spanned(r#""a command""#), e)).is_empty());
+        assert_eq!(
+            errors(|e| validate_description(&spanned(r#""""#), e)),
+            vec![error("description must be 1-100 characters", r#""""#)]
+        );
+        let long = format!(r#""{}""#, "a".repeat(101));
+        assert_eq!(
+            errors(|e| validate_description(&spanned(&long), e)),
+            vec![error("description must be 1-100 characters", &long)]
+        );
+    }
+
+    #[test]
+    fn checks_parameter_bounds() {
+        let name = spanned(r#""size""#);
+        let check = |source: &str| errors(|e| validate_parameter(&name, None, &args(source), e));
+
+        assert!(check("min = 1, max = 5, min_length = 0, max_length = 6000").is_empty());
+        assert_eq!(
+            check("min = 5, max = 1"),
+            vec![error("`min` must not exceed `max`", r#""size""#)]
+        );
+        assert_eq!(
+            check("min_value_int = 3, max_value_int = 2"),
+            vec![error(
+                "`min_value_int` must not exceed `max_value_int`",
+                r#""size""#
+            )]
+        );
+        assert_eq!(
+            check("min_length = 6001, max_length = 7000"),
+            vec![
+                error("lengths must be between 0 and 6000", r#""size""#),
+                error("lengths must be between 0 and 6000", r#""size""#),
+            ]
+        );
+        assert_eq!(
+            check(r#"choice(name = "a", value_int = 1), choice(name = "b", value_string = "b")"#),
+            vec![error("choices must all have the same value type", r#""b""#)]
+        );
+        assert_eq!(
+            check(r#"choice(name = "a"), autocomplete = complete"#),
+            vec![
+                error(
+                    "expected one of `value_string`, `value_int` or `value_number`",
+                    r#""a""#
+                ),
+                error(
+                    "`autocomplete` cannot be combined with choices",
+                    r#""size""#
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_list_counts() {
+        let name = spanned(r#""role""#);
+        let check = |counts, rest| errors(|e| validate_list(&name, counts, rest, e));
+
+        assert!(check((1, Some(25)), false).is_empty());
+        assert!(check((0, None), true).is_empty());
+        assert_eq!(
+            check((3, Some(2)), false),
+            vec![error(
+                "`min_count` must not exceed `max_count`",
+                r#""role""#
+            )]
+        );
+        assert_eq!(
+            check((0, Some(26)), false),
+            vec![error("`max_count` must be between 1 and 25", r#""role""#)]
+        );
+        assert_eq!(
+            check((1, None), false),
+            vec![error(
+                "`Vec` arguments need a `max_count`, or `rest`",
+                r#""role""#
+            )]
+        );
+
+        let long = format!(r#""{}""#, "a".repeat(30));
+        assert_eq!(
+            errors(|e| validate_list(&spanned(&long), (1, Some(10)), false, e)),
+            vec![error(
+                &format!("name '{}_10' must be 1-32 characters", "a".repeat(30)),
+                &long
+            )]
+        );
+    }
+}
@@ -1,6 +1,15 @@
+pub use wab_core::twilight_model;
 pub use wab_core::{
-    Argument, Bot, BoxedFuture, Command, CommandBuilder, CommandContext, CommandFunction,
-    CommandResult, Event, Group, Parameter, ParameterChoice, ParameterChoiceType, ParameterType,
-    SetupContext,
+    argument, check, error, Argument, AutocompleteContext, AutocompleteFunction, Bot, BoxedFuture,
+    Check, CheckFunction, Choices, Command, CommandBuilder, CommandContext, CommandFunction,
+    CommandGroup, CommandPermissions, CommandResult, CommandScope, CommandSource, Component,
+    ComponentContext, ComponentErrorHandler, ComponentFunction, Cooldown, CooldownBucket, CustomId,
+    CustomIdError, Error, ErrorHandler, Event, FromArgument, FromTarget, Group, Localizations,
+    Modal, ModalContext, ModalErrorHandler, ModalFunction, ModalHandler, Parameter,
+    ParameterBuilder, ParameterChoice, ParameterChoiceType, ParameterError, ParameterType,
+    Prefixes, RegistrationTarget, SetupContext, ValidationError, TARGET_ARGUMENT,
+};
+pub use wab_macro::{
+    box_async, command, command_group, component, event, group, message_command, modal,
+    user_command, Choices, Modal,
 };
-pub use wab_macro::{box_async, command, event, group};